@@ -1,25 +1,32 @@
 use crate::application::services::ports::PersistenceAdapter;
-use crate::domain::lifting::{ Lift, LiftAttempt, LiftAttemptResult};
-use crate::domain::programs::Program;
-use anyhow::Result;
+use crate::domain::lifting::{ Lift, LiftAttempt, LiftAttemptResult, LiftId};
+use crate::domain::programs::{Deload, Program};
+use crate::domain::progression::ProgressionState;
+use crate::domain::settings::Settings;
+use anyhow::{Context, Result};
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::from_str;
 use serde_json::to_string_pretty;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env::current_dir;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
-use std::fs::{create_dir_all, OpenOptions};
+use std::fs::{create_dir_all, remove_file, rename, OpenOptions};
 use std::io::Read;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use crate::domain::day::Day;
+use crate::domain::legacy;
 
 const HISTORY_SAVE_FILE_NAME: &'static str = "lift_history.txt";
 const INFO_SAVE_FILE_NAME: &'static str = "info.txt";
 const PROGRAM_SAVE_FILE_NAME: &'static str = "program.json";
 const SAVE_DIRECTORY_NAME: &'static str = "yawa_save_data";
+/// Which notation the program is saved in. Saves without one are from before names could be
+/// put in quotes, and are read the way they were written (see `legacy`). Saves before version 2
+/// keep weights and progressions under each lift's whole notation, rather than its id.
+const NOTATION_VERSION: usize = 2;
 
 pub struct FileSystem {
     save_dir: PathBuf,
@@ -39,12 +46,29 @@ pub fn new() -> Result<FileSystem> {
 struct SerializableProgram {
     name: String,
     workouts_completed: usize,
-    reference_weight: usize,
-    starting_reference_weight: usize,
+    reference_weight: f64,
+    starting_reference_weight: f64,
+    #[serde(default)]
+    reference_weights: BTreeMap<String, f64>,
+    #[serde(default)]
+    starting_reference_weights: BTreeMap<String, f64>,
     days_in_notation: Vec<String>,
-    weights: HashMap<String, usize>,
+    weights: HashMap<String, f64>,
     current_day: usize,
     past_attempt_results_in_notation: Vec<Vec<String>>,
+    #[serde(default)]
+    settings_in_notation: String,
+    #[serde(default)]
+    lift_progressions_in_notation: HashMap<String, String>,
+    /// The main reference weight's progression is under the empty name.
+    #[serde(default)]
+    reference_progressions_in_notation: BTreeMap<String, String>,
+    #[serde(default)]
+    bodyweight: Option<f64>,
+    #[serde(default)]
+    notation_version: usize,
+    #[serde(default)]
+    template: Option<(String, usize)>,
 }
 
 impl SerializableProgram {
@@ -58,6 +82,8 @@ impl SerializableProgram {
             workouts_completed: program.workouts_completed,
             reference_weight: program.reference_weight,
             starting_reference_weight: program.starting_reference_weight,
+            reference_weights: program.reference_weights.clone(),
+            starting_reference_weights: program.starting_reference_weights.clone(),
             days_in_notation: program.days.iter().map(|it| format!("{it}")).collect(),
             weights,
             current_day: program.current_day,
@@ -66,8 +92,80 @@ impl SerializableProgram {
                 .iter()
                 .map(|day| day.iter().map(|it| it.to_string()).collect())
                 .collect(),
+            settings_in_notation: program.settings.to_string(),
+            lift_progressions_in_notation: program
+                .lift_progressions
+                .iter()
+                .map(|(id, state)| (id.to_string(), state.to_string()))
+                .collect(),
+            reference_progressions_in_notation: program
+                .reference_progressions
+                .iter()
+                .map(|(reference, state)| {
+                    (reference.clone().unwrap_or_default(), state.to_string())
+                })
+                .collect(),
+            bodyweight: program.bodyweight,
+            notation_version: NOTATION_VERSION,
+            template: program.template.clone(),
         }
     }
+
+    fn parse_lift(&self, notation: &str) -> Result<Lift> {
+        Ok(self.with_equipment(match self.notation_version {
+            0 => legacy::parse_lift(notation)?,
+            _ => Lift::parse(notation)?,
+        }))
+    }
+
+    fn parse_day(&self, notation: &str) -> Result<Day> {
+        let day = match self.notation_version {
+            0 => legacy::parse_day(notation)?,
+            _ => Day::parse(notation)?,
+        };
+        Ok(Day {
+            lifts: day.lifts.into_iter().map(|lift| self.with_equipment(lift)).collect(),
+            ..day
+        })
+    }
+
+    /// Saves before version 2 can be from before lifts could say what they're done with, so
+    /// their lifts that don't say get the equipment the default program's have (see
+    /// `legacy::with_default_equipment()`).
+    fn with_equipment(&self, lift: Lift) -> Lift {
+        match self.notation_version {
+            0 | 1 => legacy::with_default_equipment(lift),
+            _ => lift,
+        }
+    }
+
+    /// Reads what's kept for each lift. Older saves keep it under the lift's whole notation, so
+    /// lifts of the same name with different sets on different days each keep their own (see
+    /// `Lift::id_in()`), and a lift whose sets were changed can be there more than once. Then
+    /// how it used to be is kept under its `LiftId` for it to go on from, unless it's the same
+    /// as it is now, and of several, the notation that sorts first is, so the same save is
+    /// always read the same way.
+    fn by_lift_id<T: Clone>(
+        &self,
+        saved: &HashMap<String, T>,
+        days: &[Day],
+    ) -> Result<HashMap<LiftId, T>> {
+        if self.notation_version >= 2 {
+            return Ok(saved.iter().map(|(id, it)| (LiftId::new(id), it.clone())).collect());
+        }
+        let mut by_id = HashMap::new();
+        let saved: BTreeMap<&String, &T> = saved.iter().collect();
+        saved.into_iter().try_for_each(|(notation, it)| {
+            let lift = self.parse_lift(notation)?;
+            if days.iter().any(|day| day.lifts.contains(&lift)) {
+                by_id.insert(lift.id_in(days), it.clone());
+            } else {
+                by_id.entry(lift.id()).or_insert_with(|| it.clone());
+            }
+            anyhow::Ok(())
+        })?;
+        Ok(by_id)
+    }
     fn parse(program_string: &String) -> Result<SerializableProgram> {
         Ok(from_str(&program_string)?)
     }
@@ -76,20 +174,53 @@ impl SerializableProgram {
 impl Program {
     fn from(serializable_program: &SerializableProgram) -> Result<Self> {
         let days = Self::read_days(&serializable_program)?;
-        let weights = Self::read_weights(&serializable_program)?;
+        let weights = serializable_program.by_lift_id(&serializable_program.weights, &days)?;
         let past_attempts = Self::read_past_attempts(&serializable_program)?;
+        let lift_progressions = Self::read_lift_progressions(serializable_program, &days)?;
+        let reference_progressions = Self::read_reference_progressions(serializable_program)?;
         Ok(Self {
             days,
             weights,
             reference_weight: serializable_program.reference_weight,
             starting_reference_weight: serializable_program.starting_reference_weight,
+            reference_weights: serializable_program.reference_weights.clone(),
+            starting_reference_weights: serializable_program.starting_reference_weights.clone(),
             workouts_completed: serializable_program.workouts_completed,
             name: serializable_program.name.clone(),
             current_day: serializable_program.current_day,
             current_cycle_attempt_results: past_attempts,
+            settings: Settings::parse(&serializable_program.settings_in_notation)?,
+            lift_progressions,
+            reference_progressions,
+            bodyweight: serializable_program.bodyweight,
+            template: serializable_program.template.clone(),
         })
     }
 
+    fn read_lift_progressions(
+        serializable_program: &SerializableProgram,
+        days: &[Day],
+    ) -> Result<HashMap<LiftId, ProgressionState>> {
+        serializable_program
+            .by_lift_id(&serializable_program.lift_progressions_in_notation, days)?
+            .into_iter()
+            .map(|(id, state)| Ok((id, ProgressionState::parse(&state)?)))
+            .collect()
+    }
+
+    fn read_reference_progressions(
+        serializable_program: &SerializableProgram,
+    ) -> Result<BTreeMap<Option<String>, ProgressionState>> {
+        serializable_program
+            .reference_progressions_in_notation
+            .iter()
+            .map(|(reference, state)| {
+                let reference = Some(reference.clone()).filter(|it| !it.is_empty());
+                Ok((reference, ProgressionState::parse(state)?))
+            })
+            .collect()
+    }
+
     fn read_past_attempts(
         serializable_program: &SerializableProgram,
     ) -> Result<Vec<Vec<LiftAttemptResult>>> {
@@ -108,21 +239,12 @@ impl Program {
         Ok(past_attempts)
     }
 
-    fn read_weights(serializable_program: &SerializableProgram) -> Result<HashMap<Lift, usize>> {
-        let mut weights = HashMap::new();
-        serializable_program.weights.iter().try_for_each(|it| {
-            weights.insert(Lift::parse(it.0)?, *it.1);
-            anyhow::Ok(())
-        })?;
-        Ok(weights)
-    }
-
     fn read_days(serializable_program: &SerializableProgram) -> Result<Vec<Day>> {
         let mut days = Vec::new();
         serializable_program
             .days_in_notation
             .iter()
-            .try_for_each(|it| anyhow::Ok(days.push(Day::parse(it)?)))?;
+            .try_for_each(|it| anyhow::Ok(days.push(serializable_program.parse_day(it)?)))?;
         Ok(days)
     }
 }
@@ -154,11 +276,27 @@ impl PersistenceAdapter for FileSystem {
         Ok(())
     }
 
+    /// Each line has the lift's id in brackets, so its history can be found by it, whatever its
+    /// sets were at the time.
     fn save_history(&self, attempt: &LiftAttempt, result: &LiftAttemptResult) -> Result<()> {
         append_string_to_file(
             &self.save_dir.display().to_string(),
             HISTORY_SAVE_FILE_NAME,
-            &format!("{}: {} | {}\n", chrono::Utc::now(), attempt, result),
+            &format!(
+                "{}: [{}] {} | {}\n",
+                chrono::Utc::now(),
+                attempt.lift.id(),
+                attempt,
+                result
+            ),
+        )
+    }
+
+    fn save_deload(&self, deload: &Deload) -> Result<()> {
+        append_string_to_file(
+            &self.save_dir.display().to_string(),
+            HISTORY_SAVE_FILE_NAME,
+            &format!("{}: {}\n", chrono::Utc::now(), deload),
         )
     }
 
@@ -169,6 +307,33 @@ impl PersistenceAdapter for FileSystem {
             SerializableProgram::parse(&program_string)?;
         Ok(Program::from(&serializable_program)?)
     }
+
+    fn read_program_definition(&self, path: &Path) -> Result<String> {
+        let mut file = File::open(path)
+            .with_context(|| format!("Could not open program file: {}", path.display()))?;
+        let mut definition = String::new();
+        file.read_to_string(&mut definition)?;
+        Ok(definition)
+    }
+
+    /// Writes the definition next to the file first, then puts it in the file's place, so the
+    /// file is never left half written.
+    fn write_program_definition(&self, path: &Path, definition: &str) -> Result<()> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+        let written = File::create(&temporary).and_then(|mut file| {
+            write!(file, "{definition}")?;
+            file.sync_all()
+        });
+        written
+            .and_then(|_| rename(&temporary, path))
+            .inspect_err(|_| {
+                let _ = remove_file(&temporary);
+            })
+            .with_context(|| format!("Could not write program file: {}", path.display()))?;
+        Ok(())
+    }
 }
 
 impl FileSystem {
@@ -218,10 +383,104 @@ mod tests {
 
     #[test]
     fn can_create_and_save_program() {
-        let program = start_gzcl_4day(100);
+        let program = start_gzcl_4day(100.0);
         let string: String = SerializableProgram::from(&program).to_string();
         let after_round_trip =
             Program::from(&SerializableProgram::parse(&string).unwrap()).unwrap();
         assert_eq!(after_round_trip, program);
     }
+
+    #[test]
+    fn can_save_performed_results() {
+        let program = start_gzcl_4day(100.0).complete_workout(&vec![
+            LiftAttemptResult::performed(vec![3, 3, 3, 3, 8], Some(20.0), Some(7.5));
+            5
+        ]);
+        let string: String = SerializableProgram::from(&program).to_string();
+        let after_round_trip =
+            Program::from(&SerializableProgram::parse(&string).unwrap()).unwrap();
+        assert_eq!(after_round_trip, program);
+    }
+
+    #[test]
+    fn reads_programs_saved_before_names_could_be_quoted() {
+        let mut saved = SerializableProgram::from(&start_gzcl_4day(100.0));
+        saved.notation_version = 0;
+        saved.days_in_notation = vec!["Plyo | 6\" box jump -> 3x5 @ add5".to_string()];
+        saved.weights = HashMap::from([("6\" box jump -> 3x5 @ add5".to_string(), 20.0)]);
+        let program =
+            Program::from(&SerializableProgram::parse(&saved.to_string()).unwrap()).unwrap();
+        assert_eq!(program.days[0].lifts[0].name, "6\" box jump");
+        assert_eq!(program.weights.values().collect::<Vec<_>>(), vec![&20.0]);
+        let resaved = SerializableProgram::from(&program);
+        assert_eq!(resaved.notation_version, NOTATION_VERSION);
+        assert_eq!(
+            resaved.days_in_notation[0],
+            "Plyo | \"6\\\" box jump\" -> 3x5 @ add5"
+        );
+        let after_round_trip =
+            Program::from(&SerializableProgram::parse(&resaved.to_string()).unwrap()).unwrap();
+        assert_eq!(after_round_trip, program);
+    }
+
+    #[test]
+    fn moves_what_was_saved_under_whole_lifts_to_their_ids() {
+        let mut saved = SerializableProgram::from(&start_gzcl_4day(100.0));
+        saved.notation_version = 1;
+        saved.days_in_notation =
+            vec!["Arms | Face pull -> 3x12 @ add5 | Curl -> 3x12 @ add5".to_string()];
+        saved.weights = HashMap::from([
+            ("Face pull -> 2x15,1x15-25 @ add5".to_string(), 60.0),
+            ("Curl -> 3x10 @ add5".to_string(), 25.0),
+            ("Curl -> 3x12 @ add5".to_string(), 20.0),
+        ]);
+        saved.lift_progressions_in_notation =
+            HashMap::from([("Face pull -> 2x15,1x15-25 @ add5".to_string(), "stalls=1".to_string())]);
+        let program =
+            Program::from(&SerializableProgram::parse(&saved.to_string()).unwrap()).unwrap();
+        assert_eq!(program.weights[&LiftId::new("Face pull")], 60.0);
+        assert_eq!(program.weights[&LiftId::new("Curl")], 20.0);
+        assert_eq!(
+            program.lift_progressions[&LiftId::new("Face pull")],
+            ProgressionState::parse("stalls=1").unwrap()
+        );
+        let resaved = SerializableProgram::from(&program);
+        assert_eq!(resaved.weights["face pull"], 60.0);
+        // Without the lift as it is in the program, the notation that sorts first wins.
+        saved.weights = HashMap::from([
+            ("Curl -> 3x8 @ add5".to_string(), 30.0),
+            ("Curl -> 3x10 @ add5".to_string(), 25.0),
+            ("Curl -> 3x15 @ add5".to_string(), 15.0),
+        ]);
+        (0..10).for_each(|_| {
+            let program =
+                Program::from(&SerializableProgram::parse(&saved.to_string()).unwrap()).unwrap();
+            assert_eq!(program.weights[&LiftId::new("Curl")], 25.0);
+        });
+        let after_round_trip =
+            Program::from(&SerializableProgram::parse(&resaved.to_string()).unwrap()).unwrap();
+        assert_eq!(after_round_trip, program);
+        // Lifts of the same name with different sets keep their own weights.
+        saved.days_in_notation = vec![
+            "Heavy | Squat -> 5x5 @ add10".to_string(),
+            "Light | Squat -> 3x8 @ add5".to_string(),
+        ];
+        saved.weights = HashMap::from([
+            ("Squat -> 5x5 @ add10".to_string(), 200.0),
+            ("Squat -> 3x8 @ add5".to_string(), 150.0),
+        ]);
+        let program =
+            Program::from(&SerializableProgram::parse(&saved.to_string()).unwrap()).unwrap();
+        let weights: Vec<Option<f64>> = program
+            .days
+            .iter()
+            .map(|day| program.weights.get(&day.lifts[0].id_in(&program.days)).copied())
+            .collect();
+        assert_eq!(weights, vec![Some(200.0), Some(150.0)]);
+        let after_round_trip = Program::from(
+            &SerializableProgram::parse(&SerializableProgram::from(&program).to_string()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(after_round_trip, program);
+    }
 }
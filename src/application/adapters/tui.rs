@@ -1,12 +1,21 @@
 use crate::application::services::ports::UserInputAdapter;
 use crate::domain::lifting::{LiftAttempt, LiftAttemptResult};
-use crate::domain::set::Set;
-use crate::domain::user_input::Action;
+use crate::domain::plates::Equipment;
+use crate::domain::set::{Set, RPE_SCALE};
+use crate::domain::timing::Rest;
+use crate::domain::units::Unit;
+use crate::domain::warmups::WarmupSet;
+use crate::domain::user_input::{Action, ProgramSource};
+use crate::domain::weight_scheme::WeightScheme;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::fmt::Display;
 use std::io;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::Duration;
 
 #[derive(Debug, Parser)]
 #[clap(author, version, about)]
@@ -23,10 +32,33 @@ struct Args {
 enum Commands {
     /// Start a new weightlifting routine! Let's GOOOoOoOo!!!!!1
     Start {
-        /// The reference weight to start with (in lbs). 45 is a good number to start with if it's
-        /// your first time.
-        #[clap(short)]
-        reference_weight: usize,
+        /// The reference weight to start with, in the program's unit (lb, unless it says otherwise).
+        /// 45 is a good number to start with if it's your first time. Optional when starting from a program file or template, which have
+        /// their own.
+        #[clap(short, required_unless_present_any = ["program", "template"])]
+        reference_weight: Option<f64>,
+
+        /// A named reference weight to start with, like 'squat=185', for programs that have them.
+        /// Can be given more than once.
+        #[clap(short = 'R', long = "reference", value_parser = parse_named_reference_weight)]
+        named_reference_weights: Vec<(String, f64)>,
+
+        /// A file defining the program to start, instead of the default one. Looks like:
+        /// 'Name !!! 135 !!! Day One | Squat -> 3x5 @ 1r | Leg press -> 3x10 @ add20 !! Day Two | ...
+        /// !!! Leg press = 90'
+        #[clap(short, long, conflicts_with = "template")]
+        program: Option<PathBuf>,
+
+        /// The name of a built-in program to start, instead of the default one. Run 'programs list'
+        /// to see them.
+        #[clap(short, long)]
+        template: Option<String>,
+    },
+
+    /// Browse the programs that come with yawa.
+    Programs {
+        #[clap(subcommand)]
+        command: ProgramsCommands,
     },
 
     /// Display current status of your lifting lifting.
@@ -37,6 +69,54 @@ enum Commands {
 
     /// Complete the next workout of your program! (Run 'next' to see it first!)
     Complete {},
+
+    /// Switch your program to another unit, converting every weight in it.
+    ConvertUnits {
+        /// The unit to switch to: 'lb' or 'kg'.
+        #[clap(value_parser = parse_unit)]
+        unit: Unit,
+    },
+
+    /// Work out which plates to load the bar with for a weight.
+    Plates {
+        /// The weight to load, in the program's unit.
+        weight: f64,
+    },
+
+    /// Log what you weigh, to work out the load of bodyweight lifts.
+    Bodyweight {
+        /// Your bodyweight, in the program's unit.
+        weight: f64,
+    },
+
+    /// Check a program file for problems, including ones that don't stop it from starting,
+    /// like a rep range from more reps to fewer.
+    Lint {
+        /// The program file to check.
+        file: PathBuf,
+    },
+
+    /// Rewrite a program file in the usual notation, with one day to a line.
+    Fmt {
+        /// The program file to rewrite.
+        file: PathBuf,
+
+        /// Only check that the file is already formatted, failing if it isn't.
+        #[clap(long)]
+        check: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ProgramsCommands {
+    /// List the built-in programs.
+    List {},
+
+    /// Show the days of a built-in program.
+    Show {
+        /// The name of the program, as shown by 'programs list'.
+        name: String,
+    },
 }
 
 pub struct Tui {}
@@ -46,45 +126,384 @@ pub fn new() -> Tui {
 
 impl UserInputAdapter for Tui {
     fn check_complete(&self, attempts: &[LiftAttempt]) -> Result<Vec<LiftAttemptResult>> {
-        attempts.iter().map(ask_user_for_attempt_result).collect()
+        let mut results = Vec::new();
+        let mut index = 0;
+        while index < attempts.len() {
+            let superset = attempts[index].superset;
+            let together = attempts[index..]
+                .iter()
+                .take_while(|it| superset.is_some() && it.superset == superset)
+                .count()
+                .max(1);
+            if together == 1 {
+                results.push(ask_user_for_attempt_result(&attempts[index])?);
+            } else {
+                results.extend(ask_user_for_superset_results(
+                    &attempts[index..index + together],
+                )?);
+            }
+            index += together;
+        }
+        Ok(results)
     }
 
     fn ask_what_to_do(&self) -> Result<(Action, Option<PathBuf>)> {
         let args = Args::parse();
         Ok((
             match args.command {
-                Commands::Start { reference_weight } => Action::StartProgram {
-                    reference_weight: reference_weight,
+                Commands::Start {
+                    reference_weight,
+                    named_reference_weights,
+                    program,
+                    template,
+                } => Action::StartProgram {
+                    reference_weight,
+                    named_reference_weights,
+                    source: match (program, template) {
+                        (Some(file), _) => ProgramSource::File(file),
+                        (None, Some(name)) => ProgramSource::Template(name),
+                        (None, None) => ProgramSource::Default,
+                    },
                 },
+                Commands::Programs {
+                    command: ProgramsCommands::List {},
+                } => Action::ListPrograms,
+                Commands::Programs {
+                    command: ProgramsCommands::Show { name },
+                } => Action::ShowProgram { name },
                 Commands::Status {} => Action::SeeStatus,
                 Commands::Next {} => Action::SeeNextDay,
                 Commands::Complete {} => Action::CompleteDay,
+                Commands::Plates { weight } => Action::ShowPlates { weight },
+                Commands::Bodyweight { weight } => Action::LogBodyweight { weight },
+                Commands::ConvertUnits { unit } => Action::ConvertUnits { unit },
+                Commands::Lint { file } => Action::LintProgram { path: file },
+                Commands::Fmt { file, check } => Action::FormatProgram { path: file, check },
             },
             args.save_directory,
         ))
     }
 }
 
+fn parse_named_reference_weight(notation: &str) -> Result<(String, f64), String> {
+    let (name, weight) = notation
+        .split_once('=')
+        .ok_or(format!("expected 'name=weight', got '{notation}'"))?;
+    let weight = weight
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a weight", weight.trim()))?;
+    Ok((name.trim().to_string(), weight))
+}
+
+fn parse_unit(notation: &str) -> Result<Unit, String> {
+    Unit::parse(notation).map_err(|error| error.to_string())
+}
+
 fn ask_user_for_attempt_result(attempt: &LiftAttempt) -> Result<LiftAttemptResult, anyhow::Error> {
-    if did_complete_lift(attempt)? {
-        return Ok(LiftAttemptResult::Completed {
-            completed_maximum_reps: did_complete_maximum_reps(attempt)?,
-        });
-    } else {
+    if !did_attempt_lift(attempt)? {
         return Ok(LiftAttemptResult::NotCompleted);
     }
+    let sets = attempt.lift.all_sets();
+    let reps = sets
+        .iter()
+        .enumerate()
+        .map(|(index, set)| {
+            let reps = ask_for_set(attempt, "", index, sets.len(), set)?;
+            if index + 1 < sets.len() {
+                rest_between_sets(attempt.lift.rest)?;
+            }
+            Ok(reps)
+        })
+        .collect::<Result<Vec<Vec<Vec<usize>>>>>()?;
+    ask_for_performance(attempt, reps, "")
+}
+
+/// Asks about lifts done as a superset, in the order their sets were done:
+/// the first set of each lift, then the second set of each, and so on.
+fn ask_user_for_superset_results(attempts: &[LiftAttempt]) -> Result<Vec<LiftAttemptResult>> {
+    println!(
+        "Superset: {}",
+        attempts
+            .iter()
+            .map(|attempt| attempt.lift.name.clone())
+            .collect::<Vec<String>>()
+            .join(" + ")
+    );
+    let attempted = attempts
+        .iter()
+        .map(did_attempt_lift)
+        .collect::<Result<Vec<bool>>>()?;
+    let sets: Vec<Vec<Set>> = attempts.iter().map(|it| it.lift.all_sets()).collect();
+    let rounds = sets.iter().map(|it| it.len()).max().unwrap_or_default();
+    // Rest comes after each round of the superset, as long as its longest rest.
+    let rest = attempts
+        .iter()
+        .filter_map(|attempt| attempt.lift.rest)
+        .max_by_key(|rest| rest.duration);
+    let mut reps: Vec<Vec<Vec<Vec<usize>>>> = vec![Vec::new(); attempts.len()];
+    for round in 0..rounds {
+        for (index, attempt) in attempts.iter().enumerate() {
+            if let (true, Some(set)) = (attempted[index], sets[index].get(round)) {
+                let prefix = format!("{}: ", attempt.lift.name);
+                reps[index].push(ask_for_set(attempt, &prefix, round, sets[index].len(), set)?);
+            }
+        }
+        if round + 1 < rounds && attempted.contains(&true) {
+            rest_between_sets(rest)?;
+        }
+    }
+    attempts
+        .iter()
+        .zip(attempted)
+        .zip(reps)
+        .map(|((attempt, attempted), reps)| match attempted {
+            true => ask_for_performance(attempt, reps, &format!("{}: ", attempt.lift.name)),
+            false => Ok(LiftAttemptResult::NotCompleted),
+        })
+        .collect()
+}
+
+/// Asks for the rest of what was done, once the reps of each set (on each side, and in each
+/// of their parts) are known.
+fn ask_for_performance(
+    attempt: &LiftAttempt,
+    reps: Vec<Vec<Vec<usize>>>,
+    prefix: &str,
+) -> Result<LiftAttemptResult> {
+    // Distances and calories were asked how many seconds they took, and are done once timed.
+    let (reps, time_taken): (Vec<Vec<Vec<usize>>>, Vec<Option<Duration>>) = attempt
+        .lift
+        .all_sets()
+        .iter()
+        .zip(reps)
+        .map(|(set, sides)| match set.without_effort() {
+            Set::Distance { .. } | Set::Calories { .. } => {
+                let time = sides
+                    .first()
+                    .and_then(|it| it.first())
+                    .filter(|seconds| **seconds > 0)
+                    .map(|seconds| Duration::from_secs(*seconds as u64));
+                (vec![vec![time.is_some() as usize]; sides.len()], time)
+            }
+            _ => (sides, None),
+        })
+        .unzip();
+    let weight = ask_for_weight_used(attempt, prefix)?;
+    let back_off_weights = ask_for_back_off_weights_used(attempt, prefix)?;
+    let rpe = get_user_rpe(&rpe_question(attempt, prefix))?;
+    let performed = match attempt.lift.per_side {
+        true => LiftAttemptResult::performed_per_side(
+            reps.into_iter()
+                .map(|sides| [sides[0].clone(), sides[1].clone()])
+                .collect(),
+            weight,
+            rpe,
+        ),
+        false => LiftAttemptResult::performed_in_parts(reps.into_iter().flatten().collect(), weight, rpe),
+    };
+    Ok(performed
+        .with_back_off_weights(back_off_weights)
+        .with_time_taken(time_taken))
+}
+
+/// Asks for an RPE until one on the scale is given, or the question is skipped.
+fn get_user_rpe(prompt: &str) -> Result<Option<f64>> {
+    loop {
+        match get_user_number(prompt, None)? {
+            Some(rpe) if !RPE_SCALE.contains(&rpe) => {
+                println!("        ... an RPE is from 1 to 10")
+            }
+            rpe => return Ok(rpe),
+        }
+    }
+}
+
+fn rpe_question(attempt: &LiftAttempt, prefix: &str) -> String {
+    let question = format!("        ... {prefix}how hard was it, as an RPE from 1 to 10?");
+    match attempt.lift.effort() {
+        Some(effort) => format!("{question} (target: {effort})"),
+        None => question,
+    }
+}
+
+fn did_attempt_lift(attempt: &LiftAttempt) -> Result<bool, anyhow::Error> {
+    if !attempt.warmups.is_empty() {
+        println!(
+            "Warm up for {} with: {}",
+            attempt.lift.name,
+            WarmupSet::display_all(&attempt.warmups)
+        );
+    }
+    get_user_confirmation(&format!("Did you attempt: {}?", attempt))
+}
+
+/// Asks for the reps of a set, or of each of its parts for sets done in parts, like drop sets.
+/// Lifts done per side are asked about the left side, then the right.
+fn ask_for_set(
+    attempt: &LiftAttempt,
+    prefix: &str,
+    index: usize,
+    total: usize,
+    set: &Set,
+) -> Result<Vec<Vec<usize>>> {
+    if !attempt.lift.per_side {
+        return Ok(vec![ask_for_side(attempt, prefix, index, total, set)?]);
+    }
+    let left = ask_for_side(attempt, &format!("{prefix}left side, "), index, total, set)?;
+    let right = ask_for_side(attempt, &format!("{prefix}right side, "), index, total, set)?;
+    Ok(vec![left, right])
+}
+
+fn ask_for_side(
+    attempt: &LiftAttempt,
+    prefix: &str,
+    index: usize,
+    total: usize,
+    set: &Set,
+) -> Result<Vec<usize>> {
+    let parts = set.parts();
+    if parts.is_empty() {
+        return Ok(vec![ask_for_reps(prefix, index, total, set)?]);
+    }
+    let drop_weights = attempt.drop_weights(index);
+    parts
+        .iter()
+        .enumerate()
+        .map(|(part, target)| {
+            let weight = match part.checked_sub(1).and_then(|it| drop_weights.get(it)) {
+                Some(weight) => format!(" at {} {}", weight, attempt.unit),
+                None => String::new(),
+            };
+            Ok(get_user_number(
+                &format!(
+                    "        ... {}set {} of {} ({}), part {} of {}{}: how many reps?",
+                    prefix,
+                    index + 1,
+                    total,
+                    set,
+                    part + 1,
+                    parts.len(),
+                    weight
+                ),
+                *target,
+            )?
+            .unwrap_or_default())
+        })
+        .collect()
 }
 
-fn did_complete_maximum_reps(attempt: &LiftAttempt) -> Result<bool, anyhow::Error> {
-    if attempt.has_rep_range() {
-        get_user_confirmation(&"        ... were you able to achieve the maximum rep range?")
-    } else {
-        Ok(true)
+fn ask_for_reps(prefix: &str, index: usize, total: usize, set: &Set) -> Result<usize, anyhow::Error> {
+    let (unit, default) = match set.without_effort() {
+        Set::Time { .. } => ("seconds", Some(set.maximum_reps())),
+        Set::Distance { .. } | Set::Calories { .. } => ("seconds did it take", None),
+        Set::Rounds { .. } => ("rounds", None),
+        Set::Any => ("reps", Some(0)),
+        _ => ("reps", Some(set.maximum_reps())),
+    };
+    Ok(get_user_number(
+        &format!(
+            "        ... {}set {} of {} ({}): how many {}?",
+            prefix,
+            index + 1,
+            total,
+            set,
+            unit
+        ),
+        default,
+    )?
+    .unwrap_or_default())
+}
+
+fn ask_for_weight_used(attempt: &LiftAttempt, prefix: &str) -> Result<Option<f64>, anyhow::Error> {
+    match attempt.lift.weight {
+        WeightScheme::None => Ok(None),
+        _ if attempt.lift.done_with() == Equipment::Bodyweight => get_user_number(
+            &format!(
+                "        ... {}what weight did you add ({}, below zero for assistance)?",
+                prefix, attempt.unit
+            ),
+            attempt.prescribed_weight(),
+        ),
+        _ => get_user_number(
+            &format!(
+                "        ... {}what weight did you use ({}{})?",
+                prefix,
+                attempt.unit,
+                if attempt.lift.per_hand { " per hand" } else { "" }
+            ),
+            attempt.prescribed_weight(),
+        ),
     }
 }
 
-fn did_complete_lift(attempt: &LiftAttempt) -> Result<bool, anyhow::Error> {
-    get_user_confirmation(&format!("Did you complete: {}?", attempt))
+/// Asks for the weight used for each back-off set group, by the sets in it.
+fn ask_for_back_off_weights_used(attempt: &LiftAttempt, prefix: &str) -> Result<Vec<Option<f64>>> {
+    let mut first = attempt.lift.sets.len() + 1;
+    attempt
+        .lift
+        .back_off
+        .iter()
+        .zip(attempt.prescribed_back_off_weights())
+        .map(|(group, prescribed)| {
+            let last = first + group.sets.len() - 1;
+            let sets = match first == last {
+                true => format!("set {first}"),
+                false => format!("sets {first} to {last}"),
+            };
+            first = last + 1;
+            match group.weight {
+                WeightScheme::None => Ok(None),
+                _ => get_user_number(
+                    &format!(
+                        "        ... {}what weight did you use for {} ({}{})?",
+                        prefix,
+                        sets,
+                        attempt.unit,
+                        if attempt.lift.per_hand { " per hand" } else { "" }
+                    ),
+                    prescribed,
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Counts down the rest before the next set, when someone is there to watch it.
+fn rest_between_sets(rest: Option<Rest>) -> Result<()> {
+    let Some(rest) = rest else {
+        return Ok(());
+    };
+    println!("        ... rest {rest}");
+    if !io::stdout().is_terminal() {
+        return Ok(());
+    }
+    (1..=rest.duration.as_secs()).rev().try_for_each(|remaining| {
+        print!("\r        ... {}:{:02} to go ", remaining / 60, remaining % 60);
+        io::stdout().flush()?;
+        sleep(Duration::from_secs(1));
+        anyhow::Ok(())
+    })?;
+    println!("\r        ... time for the next set!");
+    Ok(())
+}
+
+/// Asks for a number until one is given. An empty answer gives the default,
+/// or nothing if there is no default.
+fn get_user_number<T: FromStr + Display>(prompt: &str, default: Option<T>) -> Result<Option<T>> {
+    loop {
+        match &default {
+            Some(default) => print!("{} [enter for {}] ", prompt, default),
+            None => print!("{} [enter to skip] ", prompt),
+        }
+        io::stdout().flush()?;
+        let string = read_string_from_stdin()?;
+        if string.trim().is_empty() {
+            return Ok(default);
+        } else if let Ok(number) = string.trim().parse() {
+            return Ok(Some(number));
+        }
+    }
 }
 
 fn get_user_confirmation(prompt: &str) -> Result<bool> {
@@ -110,12 +529,3 @@ fn read_string_from_stdin() -> Result<String, std::io::Error> {
     io::stdin().read_line(&mut string)?;
     return Ok(string);
 }
-
-impl LiftAttempt {
-    fn has_rep_range(&self) -> bool {
-        self.lift
-            .sets
-            .iter()
-            .any(|it| matches!(it, Set::Range { .. }))
-    }
-}
@@ -1,9 +1,12 @@
-use crate::domain::lifting::LiftAttempt;
+use crate::domain::lifting::{Lift, LiftAttempt};
 use crate::application::services::ports::{PersistenceAdapter, UserInputAdapter};
 use crate::application::services::service;
 use crate::application::services::service::apply_save_dir;
-use crate::domain::user_input::Action;
-use anyhow::Result;
+use crate::domain::units::Unit;
+use crate::domain::warmups::WarmupSet;
+use crate::domain::user_input::{Action, ProgramSource};
+use anyhow::{anyhow, Result};
+use std::path::Path;
 
 pub fn start_ephemeral_interface(
     persistence_adapter: impl PersistenceAdapter,
@@ -13,9 +16,25 @@ pub fn start_ephemeral_interface(
     let persistence_adapter = apply_save_dir(persistence_adapter, save_path);
     match action {
         Action::SeeStatus => status(&persistence_adapter)?,
-        Action::StartProgram { reference_weight } => start(&persistence_adapter, &reference_weight)?,
+        Action::StartProgram {
+            reference_weight,
+            named_reference_weights,
+            source,
+        } => start(
+            &persistence_adapter,
+            reference_weight,
+            &named_reference_weights,
+            &source,
+        )?,
+        Action::ListPrograms => list_programs(),
+        Action::ShowProgram { name } => show_program(&name)?,
         Action::SeeNextDay => next(&persistence_adapter)?,
         Action::CompleteDay => complete(&persistence_adapter, user_input_adapter)?,
+        Action::ConvertUnits { unit } => convert_units(&persistence_adapter, unit)?,
+        Action::ShowPlates { weight } => plates(&persistence_adapter, weight)?,
+        Action::LogBodyweight { weight } => log_bodyweight(&persistence_adapter, weight)?,
+        Action::LintProgram { path } => lint(&persistence_adapter, &path)?,
+        Action::FormatProgram { path, check } => format(&persistence_adapter, &path, check)?,
     };
     Ok(())
 }
@@ -24,40 +43,218 @@ fn complete(
     persistence_adapter: &impl PersistenceAdapter,
     user_input_adapter: &impl UserInputAdapter,
 ) -> Result<()> {
-    service::complete_workout(persistence_adapter, user_input_adapter)?;
+    let (volume, unit, deloads) =
+        service::complete_workout(persistence_adapter, user_input_adapter)?;
+    if volume > 0.0 {
+        println!("Volume: {volume} {unit}");
+    }
+    deloads.iter().for_each(|deload| println!("{deload}"));
     println!("Well done!");
     Ok(())
 }
 
-fn next(persistence_adapter: &impl PersistenceAdapter) -> Result<()> {
-    let (day_name, lift_attempts) = service::next_workout(persistence_adapter)?;
+fn convert_units(persistence_adapter: &impl PersistenceAdapter, unit: Unit) -> Result<()> {
+    let program = service::convert_units(persistence_adapter, unit)?;
     println!(
-        "{}",
-        format!("=== Day: {} ===\n{}", day_name, to_string(&lift_attempts))
+        "Converted program to {}. Current reference weight: {} {}",
+        unit, program.reference_weight, unit
     );
     Ok(())
 }
 
-fn to_string(lift_attempts: &Vec<LiftAttempt>) -> String {
+fn log_bodyweight(persistence_adapter: &impl PersistenceAdapter, weight: f64) -> Result<()> {
+    let program = service::log_bodyweight(persistence_adapter, weight)?;
+    println!("Logged bodyweight: {} {}", weight, program.unit());
+    Ok(())
+}
+
+fn lint(persistence_adapter: &impl PersistenceAdapter, path: &Path) -> Result<()> {
+    service::lint_program(persistence_adapter, path)?;
+    println!("No problems found in {}", path.display());
+    Ok(())
+}
+
+fn format(persistence_adapter: &impl PersistenceAdapter, path: &Path, check: bool) -> Result<()> {
+    let changed = service::format_program(persistence_adapter, path, check)?;
+    match (changed, check) {
+        (false, _) => println!("Already formatted: {}", path.display()),
+        (true, false) => println!("Formatted: {}", path.display()),
+        (true, true) => {
+            return Err(anyhow!(
+                "Not formatted: {}. Run 'fmt' without '--check' to format it.",
+                path.display()
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn plates(persistence_adapter: &impl PersistenceAdapter, weight: f64) -> Result<()> {
+    println!("Plates: {}", service::plate_loading(persistence_adapter, weight)?);
+    Ok(())
+}
+
+fn next(persistence_adapter: &impl PersistenceAdapter) -> Result<()> {
+    let (day_name, lift_attempts) = service::next_workout(persistence_adapter)?;
+    println!("=== Day: {} ===\n{}", day_name, to_string(&lift_attempts));
+    Ok(())
+}
+
+/// Lifts in a superset are labelled like A1, A2 (then B1, B2 for the next superset),
+/// so they show as a block to alternate between.
+fn to_string(lift_attempts: &[LiftAttempt]) -> String {
     let lifts = lift_attempts
         .iter()
-        .map(|lift| lift.to_string())
+        .enumerate()
+        .map(|(index, lift)| {
+            let label = match lift.superset {
+                Some(superset) => {
+                    let position = lift_attempts[..index]
+                        .iter()
+                        .filter(|it| it.superset == Some(superset))
+                        .count();
+                    format!("{}{}. ", char::from(b'A' + (superset % 26) as u8), position + 1)
+                }
+                None => String::new(),
+            };
+            let mut lines = vec![format!("{label}{lift}")];
+            if !lift.warmups.is_empty() {
+                lines.push(format!("    Warm-up: {}", WarmupSet::display_all(&lift.warmups)));
+            }
+            if let Some(plates) = &lift.plates {
+                lines.push(format!("    Plates: {plates}"));
+            }
+            if let Some(drops) = drop_weights(lift) {
+                lines.push(format!("    Drops: {drops}"));
+            }
+            if let Some(load) = lift.effective_load() {
+                lines.push(format!("    Load: {} {} with your bodyweight", load, lift.unit));
+            }
+            if let Some(rest) = &lift.lift.rest {
+                lines.push(format!("    Rest: {rest} between sets"));
+            }
+            if let Some(tempo) = &lift.lift.tempo {
+                lines.push(format!("    Tempo: {tempo}, {}", time_under_tension(&lift.lift)));
+            }
+            lines.join("\n")
+        })
         .collect::<Vec<String>>()
         .join("\n");
     lifts
 }
 
-fn start(persistence_adapter: &impl PersistenceAdapter, reference_weight: &usize) -> Result<()> {
-    let program = service::start_and_save_new_program(persistence_adapter, *reference_weight)?;
+/// Like '160 lb, 125 lb', for the first drop set with a weight to drop from.
+fn drop_weights(lift: &LiftAttempt) -> Option<String> {
+    let weights = (0..lift.lift.all_sets().len())
+        .map(|index| lift.drop_weights(index))
+        .find(|weights| !weights.is_empty())?;
+    Some(
+        weights
+            .iter()
+            .map(|weight| format!("{} {}", weight, lift.unit))
+            .collect::<Vec<String>>()
+            .join(", "),
+    )
+}
+
+/// Like '12s under tension per set', or each set's time if they differ.
+fn time_under_tension(lift: &Lift) -> String {
+    let times: Vec<u64> = lift
+        .time_under_tension()
+        .iter()
+        .map(|time| time.as_secs())
+        .collect();
+    match times.windows(2).all(|pair| pair[0] == pair[1]) {
+        true => format!("{}s under tension per set", times.first().unwrap_or(&0)),
+        false => format!(
+            "{} under tension",
+            times
+                .iter()
+                .map(|time| format!("{time}s"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn start(
+    persistence_adapter: &impl PersistenceAdapter,
+    reference_weight: Option<f64>,
+    named_reference_weights: &[(String, f64)],
+    source: &ProgramSource,
+) -> Result<()> {
+    let program = service::start_and_save_new_program(
+        persistence_adapter,
+        reference_weight,
+        named_reference_weights,
+        source,
+    )?;
     println!("Started program: {}", program.name);
     Ok(())
 }
 
+fn list_programs() {
+    service::program_templates().iter().for_each(|template| {
+        println!(
+            "{} (v{}): {}",
+            template.name, template.version, template.description
+        )
+    });
+}
+
+fn show_program(name: &str) -> Result<()> {
+    let template = service::program_template(name)?;
+    let new_program = template.new_program();
+    println!(
+        "{} (v{}): {}\n{}\nStarting reference weight: {}",
+        template.name,
+        template.version,
+        new_program.name,
+        template.description,
+        new_program.starting_reference_weight
+    );
+    new_program
+        .starting_reference_weights
+        .iter()
+        .for_each(|(name, weight)| println!("Starting '{name}' reference weight: {weight}"));
+    new_program.days.iter().for_each(|day| {
+        println!("=== Day: {} ===", day.name);
+        day.lifts.iter().for_each(|lift| match new_program.weights.get(&lift.id()) {
+            Some(weight) => println!("{lift} (starting at {weight})"),
+            None => println!("{lift}"),
+        });
+    });
+    Ok(())
+}
+
 fn status(persistence_adapter: &impl PersistenceAdapter) -> Result<()> {
     let program = service::get_program(persistence_adapter)?;
+    let unit = program.unit();
     println!(
-        "Current program: {}\nCurrent reference weight: {}\nStarting reference weight: {}\nWorkouts completed: {}",
-        program.name, program.reference_weight, program.starting_reference_weight, program.workouts_completed
+        "Current program: {}\nCurrent reference weight: {} {}\nStarting reference weight: {} {}\nWorkouts completed: {}",
+        program.name, program.reference_weight, unit, program.starting_reference_weight, unit, program.workouts_completed
     );
+    if let Some(bodyweight) = program.bodyweight {
+        println!("Bodyweight: {bodyweight} {unit}");
+    }
+    if let (Some((name, version)), Some(template)) =
+        (&program.template, service::changed_template(&program))
+    {
+        println!(
+            "The '{name}' template is at v{} now, and this program was started from v{version}. Start it again to use the new version.",
+            template.version
+        );
+    }
+    program.reference_weights.iter().for_each(|(name, weight)| {
+        println!(
+            "Current '{}' reference weight: {} {}\nStarting '{}' reference weight: {} {}",
+            name,
+            weight,
+            unit,
+            name,
+            program.starting_reference_weights.get(name).unwrap_or(weight),
+            unit
+        )
+    });
     Ok(())
 }
@@ -2,14 +2,17 @@ use anyhow::Result;
 use std::path::{Path, PathBuf};
 
 use crate::domain::lifting::{LiftAttempt, LiftAttemptResult};
-use crate::domain::programs::Program;
+use crate::domain::programs::{Deload, Program};
 use crate::domain::user_input::Action;
 
 pub trait PersistenceAdapter {
     fn set_save_dir(self, dir: &Path) -> Self;
     fn persist(&self, program: &Program) -> Result<()>;
     fn save_history(&self, attempt: &LiftAttempt, result: &LiftAttemptResult) -> Result<()>;
+    fn save_deload(&self, deload: &Deload) -> Result<()>;
     fn summon(&self) -> Result<Program>;
+    fn read_program_definition(&self, path: &Path) -> Result<String>;
+    fn write_program_definition(&self, path: &Path, definition: &str) -> Result<()>;
 }
 
 pub trait UserInputAdapter {
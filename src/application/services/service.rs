@@ -1,10 +1,16 @@
 use crate::application::services::ports::{PersistenceAdapter, UserInputAdapter};
-use crate::domain::lifting::LiftAttempt;
-use crate::domain::programs::{start_gzcl_4day, Program};
+use crate::domain::lifting::{workout_volume, LiftAttempt};
+use crate::domain::plates::{PlateInventory, PlateLoading};
+use crate::domain::programs::{Deload, NewProgram, Program, StartingReference};
+use crate::domain::templates;
+use crate::domain::templates::ProgramTemplate;
+use crate::domain::units::Unit;
+use crate::domain::user_input::ProgramSource;
 use anyhow::{anyhow, Error, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const LIFTING_PROGRAM_NOT_STARTED_ERROR_MESSAGE: &'static str = "Start a lifting program first!";
+const DEFAULT_PROGRAM_TEMPLATE: &str = "gzcl-4day";
 
 fn not_started_error() -> Result<Program, Error> {
     Err(anyhow!(LIFTING_PROGRAM_NOT_STARTED_ERROR_MESSAGE))
@@ -22,21 +28,29 @@ pub fn apply_save_dir(
     };
 }
 
+/// Completes the next workout with the results the user gives, and says how much volume was
+/// lifted in it (in the program's unit) and which weights were deloaded.
 pub fn complete_workout(
     persistence_adapter: &impl PersistenceAdapter,
     user_input_adapter: &impl UserInputAdapter,
-) -> Result<()> {
+) -> Result<(f64, Unit, Vec<Deload>)> {
     let program = get_program(persistence_adapter)?;
     let lift_attempts = program.next_workout();
     let lift_results = user_input_adapter.check_complete(&lift_attempts)?;
-    persistence_adapter.persist(&program.complete_workout(&lift_results))?;
+    let volume = workout_volume(&lift_attempts, &lift_results);
+    let unit = program.unit();
+    let (program, deloads) = program.complete_workout_with_deloads(&lift_results);
+    persistence_adapter.persist(&program)?;
     lift_attempts
         .iter()
         .enumerate()
         .try_for_each(|(index, attempt)| {
             persistence_adapter.save_history(&attempt, &lift_results[index])
         })?;
-    Ok(())
+    deloads
+        .iter()
+        .try_for_each(|deload| persistence_adapter.save_deload(deload))?;
+    Ok((volume, unit, deloads))
 }
 
 pub fn next_workout(
@@ -49,19 +63,131 @@ pub fn next_workout(
     ))
 }
 
+pub fn convert_units(persistence_adapter: &impl PersistenceAdapter, unit: Unit) -> Result<Program> {
+    let program = get_program(persistence_adapter)?.convert_units(unit);
+    persistence_adapter.persist(&program)?;
+    Ok(program)
+}
+
+pub fn log_bodyweight(persistence_adapter: &impl PersistenceAdapter, weight: f64) -> Result<Program> {
+    if !weight.is_finite() || weight <= 0.0 {
+        return Err(anyhow!("Bodyweight must be a number above zero: {weight}"));
+    }
+    let program = get_program(persistence_adapter)?.log_bodyweight(weight);
+    persistence_adapter.persist(&program)?;
+    Ok(program)
+}
+
+/// How to load the bar for the weight, with the plates of the current program (or the standard
+/// ones, if no program is started).
+pub fn plate_loading(
+    persistence_adapter: &impl PersistenceAdapter,
+    weight: f64,
+) -> Result<PlateLoading> {
+    persistence_adapter
+        .summon()
+        .map(|program| program.plate_inventory())
+        .unwrap_or(PlateInventory::standard(Unit::default()))
+        .load(weight)
+}
+
 pub fn get_program(persistence_adapter: &impl PersistenceAdapter) -> Result<Program> {
     persistence_adapter.summon().or(not_started_error())
 }
 
-fn start_program(r: usize) -> Program {
-    start_gzcl_4day(r)
+fn start_program(
+    persistence_adapter: &impl PersistenceAdapter,
+    reference_weight: Option<f64>,
+    named_reference_weights: &[(String, f64)],
+    source: &ProgramSource,
+) -> Result<Program> {
+    let (mut new_program, template) = match source {
+        ProgramSource::Default => {
+            reference_weight.ok_or(anyhow!(
+                "A reference weight is needed to start the default program."
+            ))?;
+            let template = templates::find(DEFAULT_PROGRAM_TEMPLATE)?;
+            (template.new_program(), Some(template))
+        }
+        ProgramSource::File(path) => (
+            NewProgram::parse(&persistence_adapter.read_program_definition(path)?)?,
+            None,
+        ),
+        ProgramSource::Template(name) => {
+            let template = templates::find(name)?;
+            (template.new_program(), Some(template))
+        }
+    };
+    if let Some(reference_weight) = reference_weight {
+        new_program.starting_reference_weight = reference_weight;
+    }
+    let references = new_program.references();
+    named_reference_weights
+        .iter()
+        .try_for_each(|(name, weight)| {
+            if !references.contains(name) {
+                return Err(anyhow!("This program has no reference weight named '{name}'"));
+            }
+            new_program
+                .starting_reference_weights
+                .insert(name.clone(), StartingReference::Fixed(*weight));
+            Ok(())
+        })?;
+    Ok(Program {
+        template: template.map(|template| (template.name.to_string(), template.version)),
+        ..new_program.start()
+    })
+}
+
+/// The template the program was started from, if it has changed since.
+pub fn changed_template(program: &Program) -> Option<ProgramTemplate> {
+    let (name, version) = program.template.as_ref()?;
+    templates::changed_since(name, *version)
 }
 
 pub fn start_and_save_new_program(
     persistence_adapter: &impl PersistenceAdapter,
-    reference_weight: usize,
+    reference_weight: Option<f64>,
+    named_reference_weights: &[(String, f64)],
+    source: &ProgramSource,
 ) -> Result<Program> {
-    let program = start_program(reference_weight);
+    let program = start_program(
+        persistence_adapter,
+        reference_weight,
+        named_reference_weights,
+        source,
+    )?;
     persistence_adapter.persist(&program)?;
-    return Ok(program);
+    Ok(program)
+}
+
+/// Fails with every problem in the program file, if there are any (see `NewProgram::lint()`).
+pub fn lint_program(persistence_adapter: &impl PersistenceAdapter, path: &Path) -> Result<()> {
+    NewProgram::lint(&persistence_adapter.read_program_definition(path)?)
+}
+
+/// Rewrites the program file in the usual notation (see `NewProgram::formatted()`), unless only
+/// checking it. Gives whether the file wasn't formatted already.
+pub fn format_program(
+    persistence_adapter: &impl PersistenceAdapter,
+    path: &Path,
+    check: bool,
+) -> Result<bool> {
+    let definition = persistence_adapter.read_program_definition(path)?;
+    let formatted = NewProgram::parse(&definition)?.formatted();
+    if formatted == definition {
+        return Ok(false);
+    }
+    if !check {
+        persistence_adapter.write_program_definition(path, &formatted)?;
+    }
+    Ok(true)
+}
+
+pub fn program_templates() -> Vec<ProgramTemplate> {
+    templates::all()
+}
+
+pub fn program_template(name: &str) -> Result<ProgramTemplate> {
+    templates::find(name)
 }
@@ -1,42 +1,125 @@
 use std::fmt::{Display, Formatter};
-use anyhow::anyhow;
+use std::ops::Range;
 use crate::domain::lifting::Lift;
+use crate::domain::notation::{quote, split_unquoted, unquote, NotationContext, NotationError};
+
+/// What a day's name can't have in it without being put in quotes.
+pub const NAME_SEPARATORS: [&str; 3] = [" | ", " !! ", " !!! "];
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Day {
     pub name: String,
     pub lifts: Vec<Lift>,
+    /// The lifts done together as supersets (or circuits), alternating between them,
+    /// by their indexes in `lifts`.
+    pub supersets: Vec<Range<usize>>,
 }
 
 impl Day {
+    /// Lifts are separated by ` | `. Lifts done as a superset are put in brackets and separated
+    /// by ` + `. A name with a ` | ` in it is put in quotes (see `notation::quote()`):
     /// ```
     /// # use yawa::domain::day::Day;
     /// Day::parse("Day Name | Bench press -> 3x5,1x5-6,1x6+ @ 2r | Pullup -> 3x5,1x5-6,1x6+").unwrap();
+    /// Day::parse("Day Name | Squat -> 3x5 @ 1r | [Face Pull -> 3x15 @ add5 + Cable Curl -> 3x15 @ add5]").unwrap();
+    /// Day::parse("\"Push | Pull\" | \"Curl + Press\" -> 3x10 @ add5").unwrap();
+    /// Day::parse("Day Name | [Face Pull -> 3x15 @ add5 + Cable Curl -> 3x15 @ add5").is_err();
     /// Day::parse("Something else").is_err();
     /// ```
     pub fn parse(notation: &str) -> anyhow::Result<Day> {
-        let error = "Cannot parse notation.";
-        let mut lines = notation.split(" | ");
-        let name = lines.next().ok_or(anyhow!(error))?.trim().to_string();
+        let mut lines = split_unquoted(notation, " | ").into_iter();
+        let name = unquote(lines.next().unwrap_or_default())
+            .notation("Cannot parse day name", notation)?;
+        Self::parse_lifts(name, lines, |superset| split_unquoted(superset, " + "), Lift::parse)
+    }
+
+    /// Parses the lifts of the day with the name, which has already been read from the
+    /// notation, splitting supersets and parsing each lift the given way.
+    pub fn parse_lifts<'a>(
+        name: String,
+        mut lines: impl Iterator<Item = &'a str>,
+        split_superset: impl Fn(&str) -> Vec<&str>,
+        parse_lift: impl Fn(&str) -> anyhow::Result<Lift>,
+    ) -> anyhow::Result<Day> {
         let mut lifts = Vec::new();
+        let mut supersets = Vec::new();
         lines.try_for_each(|line| {
-            return anyhow::Ok(if line.trim() != "" {
-                lifts.push(Lift::parse(line)?)
-            });
+            let line = line.trim();
+            if line.starts_with('[') {
+                let superset = line
+                    .strip_prefix('[')
+                    .and_then(|it| it.strip_suffix(']'))
+                    .ok_or_else(|| {
+                        NotationError::new("A superset must end with ']'", line)
+                            .suggesting(Some(format!("{line}]")))
+                    })?;
+                let start = lifts.len();
+                for lift in split_superset(superset) {
+                    lifts.push(parse_lift(lift).notation("Cannot parse lift", lift)?);
+                }
+                supersets.push(start..lifts.len());
+            } else if !line.is_empty() {
+                lifts.push(parse_lift(line).notation("Cannot parse lift", line)?)
+            }
+            anyhow::Ok(())
         })?;
-        Ok(Day { name, lifts })
+        Ok(Day {
+            name,
+            lifts,
+            supersets,
+        })
+    }
+
+    /// The notation of each lift of the day, in the order `parse()` reads them, as slices of
+    /// the day's notation, so a problem with a lift can be pointed at in the right one.
+    /// ```
+    /// # use yawa::domain::day::Day;
+    /// let notation = "Legs | Squat -> 3x5 @ 1r | [Squat -> 3x8 @ 0.8r + Calf raise -> 3x15]";
+    /// assert_eq!(Day::lift_notations(notation), ["Squat -> 3x5 @ 1r", "Squat -> 3x8 @ 0.8r", "Calf raise -> 3x15"]);
+    /// ```
+    pub fn lift_notations(notation: &str) -> Vec<&str> {
+        split_unquoted(notation, " | ")
+            .into_iter()
+            .skip(1)
+            .map(str::trim)
+            .flat_map(|line| match line.strip_prefix('[').and_then(|it| it.strip_suffix(']')) {
+                Some(superset) => split_unquoted(superset, " + "),
+                None => vec![line],
+            })
+            .filter(|lift| !lift.is_empty())
+            .collect()
+    }
+
+    /// Which superset of the day the lift at the index is in, if any.
+    pub fn superset_of(&self, lift_index: usize) -> Option<usize> {
+        self.supersets
+            .iter()
+            .position(|superset| superset.contains(&lift_index))
     }
 }
 
 impl Display for Day {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let lifts = self
-            .lifts
-            .iter()
-            .map(|lift| format!("{lift}"))
-            .collect::<Vec<String>>()
-            .join(" | ");
-        write!(f, "{} | {}", self.name, lifts)
+        let mut lifts: Vec<String> = Vec::new();
+        let mut index = 0;
+        while index < self.lifts.len() {
+            match self.supersets.iter().find(|superset| superset.start == index) {
+                Some(superset) => {
+                    let superset_lifts = self.lifts[superset.clone()]
+                        .iter()
+                        .map(|lift| format!("{lift}"))
+                        .collect::<Vec<String>>()
+                        .join(" + ");
+                    lifts.push(format!("[{superset_lifts}]"));
+                    index = superset.end.max(index + 1);
+                }
+                None => {
+                    lifts.push(format!("{}", self.lifts[index]));
+                    index += 1;
+                }
+            }
+        }
+        write!(f, "{} | {}", quote(&self.name, &NAME_SEPARATORS), lifts.join(" | "))
     }
 }
 
@@ -54,6 +137,7 @@ mod tests {
                     Lift::parse("Bench press -> 3x5,1x5-6,1x6+ @ 2r").unwrap(),
                     Lift::parse("Pullup -> 3x5,1x5-6,1x6+").unwrap()
                 ],
+                supersets: vec![],
             },
             Day::parse("Day Name | Bench press -> 3x5,1x5-6,1x6+ @ 2r | Pullup -> 3x5,1x5-6,1x6+")
                 .unwrap()
@@ -71,9 +155,33 @@ mod tests {
                         Lift::parse("Bench press -> 3x5,1x5-6,1x6+ @ 2r").unwrap(),
                         Lift::parse("Pullup -> 3x5,1x5-6,1x6+").unwrap()
                     ],
+                    supersets: vec![],
                 }
             ),
             "Day Name | Bench press -> 3x5,1x5-6,1x6+ @ 2r | Pullup -> 3x5,1x5-6,1x6+"
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn can_have_supersets() {
+        let notation = "Pull | Row -> 3x10 @ 1r[row] | [Face Pull -> 3x15 @ add5 + Curl -> 3x15 @ add5 + Shrug -> 3x15 @ add5] | Plank -> 1x30s";
+        let day = Day::parse(notation).unwrap();
+        assert_eq!(day.lifts.len(), 5);
+        assert_eq!(day.supersets, vec![1..4]);
+        assert_eq!(day.superset_of(2), Some(0));
+        assert_eq!(day.superset_of(4), None);
+        assert_eq!(day.to_string(), notation);
+    }
+
+    #[test]
+    fn can_have_names_in_quotes() {
+        let notation = "\"Push | Pull\" | [\"Curl + Press\" -> 3x10 @ add5 + Row -> 3x10 @ add5] | \"6\\\" box jump\" -> 3x5";
+        let day = Day::parse(notation).unwrap();
+        assert_eq!(day.name, "Push | Pull");
+        assert_eq!(day.lifts[0].name, "Curl + Press");
+        assert_eq!(day.lifts[2].name, "6\" box jump");
+        assert_eq!(day.supersets, vec![0..2]);
+        assert_eq!(day.to_string(), notation);
+        assert!(Day::parse("Push | 6\" box jump -> 3x5").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use crate::domain::day::Day;
+use crate::domain::lifting::Lift;
+use crate::domain::notation::NotationError;
+use crate::domain::templates;
+
+/// Reads a lift in the notation from before names could be put in quotes: like `Lift::parse()`,
+/// but the name is everything before the first '->', quotes and all.
+/// ```
+/// # use yawa::domain::legacy;
+/// assert_eq!(legacy::parse_lift("6\" box jump -> 3x5").unwrap().name, "6\" box jump");
+/// ```
+pub fn parse_lift(notation: &str) -> Result<Lift> {
+    let (name, sets) = notation.split_once("->").ok_or_else(|| {
+        NotationError::new("A lift needs '->' between its name and its sets", notation)
+    })?;
+    Lift::parse_named(name.trim().to_string(), sets)
+}
+
+/// Reads a day in the same notation as `parse_lift()`: like `Day::parse()`, but the day is split
+/// at every ` | ` and each superset at every ` + `.
+pub fn parse_day(notation: &str) -> Result<Day> {
+    let mut lines = notation.split(" | ");
+    let name = lines.next().unwrap_or_default().trim().to_string();
+    Day::parse_lifts(name, lines, |superset| superset.split(" + ").collect(), parse_lift)
+}
+
+/// Gives a lift from a save made before lifts could say what they're done with the equipment
+/// the lift of the same name has in the default program now, if it doesn't say. Otherwise the
+/// default program's machine, dumbbell and bodyweight lifts would all be taken for barbell
+/// lifts.
+/// ```
+/// # use yawa::domain::legacy;
+/// # use yawa::domain::lifting::Lift;
+/// # use yawa::domain::plates::Equipment;
+/// let lift = legacy::with_default_equipment(Lift::parse("Face Pull -> 3x15 @ add20").unwrap());
+/// assert_eq!(lift.done_with(), Equipment::Machine);
+/// let lift = legacy::with_default_equipment(Lift::parse("Curl -> 3x15 @ add5").unwrap());
+/// assert_eq!(lift.done_with(), Equipment::Barbell);
+/// ```
+pub fn with_default_equipment(lift: Lift) -> Lift {
+    let equipment = lift.equipment.or_else(|| {
+        templates::find("gzcl-4day")
+            .ok()?
+            .new_program()
+            .days
+            .iter()
+            .flat_map(|day| day.lifts.iter())
+            .find(|it| it.id() == lift.id())?
+            .equipment
+    });
+    Lift { equipment, ..lift }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_names_as_they_are() {
+        let day = parse_day("Push | 6\" box jump -> 3x5").unwrap();
+        assert_eq!(day.lifts[0].name, "6\" box jump");
+        assert_eq!(Day::parse(&day.to_string()).unwrap(), day);
+    }
+}
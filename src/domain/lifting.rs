@@ -1,25 +1,137 @@
 use anyhow::{anyhow, Result};
 use std::fmt::{Display, Formatter};
-use std::hash::{Hash, Hasher};
+use std::time::Duration;
+use crate::domain::day::Day;
 use crate::domain::weight_scheme::*;
 use crate::domain::set::*;
+use crate::domain::notation::{
+    count, quote, split_once_unquoted, split_unquoted, suggest, unquote, words,
+    NotationContext, NotationError,
+};
+use crate::domain::plates::{Equipment, PlateLoading};
+use crate::domain::progression::{Graduation, ProgressionScheme};
+use crate::domain::rounding::Rounding;
+use crate::domain::timing::{display_duration, parse_duration, Rest, Tempo};
+use crate::domain::units::Unit;
+use crate::domain::warmups::{WarmupScheme, WarmupSet};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Lift {
     pub name: String,
+    /// The top sets, which the lift's progression is keyed off.
     pub sets: Vec<Set>,
     pub weight: WeightScheme,
+    /// Sets done after the top sets, each group at its own weight, like back-off sets.
+    pub back_off: Vec<SetGroup>,
+    /// How this lift progresses, if not the way the program does by default.
+    pub progression: Option<ProgressionScheme>,
+    /// How this lift's weight is rounded, if not the way the program does by default.
+    pub rounding: Option<Rounding>,
+    /// What the lift is done with. A barbell, if not given.
+    pub equipment: Option<Equipment>,
+    /// How to warm up for this lift, if not the way the program does by default.
+    pub warmup: Option<WarmupScheme>,
+    /// How long to rest between sets, if the program says.
+    pub rest: Option<Rest>,
+    /// How fast to do each rep, if the program says.
+    pub tempo: Option<Tempo>,
+    /// What the lift turns into once it gets easy, if anything.
+    pub graduation: Option<Graduation>,
+    /// Whether every set is done on each side in turn, like a split squat.
+    pub per_side: bool,
+    /// Whether the weight is for each hand, like a pair of dumbbells, rather than in total.
+    pub per_hand: bool,
 }
 
-impl Eq for Lift {}
+/// Sets done at the same weight, within a lift.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetGroup {
+    pub sets: Vec<Set>,
+    pub weight: WeightScheme,
+}
+
+impl SetGroup {
+    /// How hard the group's sets should feel, if they have a target. They all have the same one.
+    pub fn effort(&self) -> Option<Effort> {
+        self.sets.first().and_then(Set::effort)
+    }
+
+    /// The group's sets, without their effort target, which is written once for the group.
+    fn sets_without_effort(&self) -> Vec<Set> {
+        self.sets.iter().map(|set| set.without_effort().clone()).collect()
+    }
+}
+
+impl Display for SetGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_notation(f, self, false, false)
+    }
+}
+
+/// Writes the group the way `Lift::parse()` reads it, marking the sets and weight of lifts
+/// done per side or per hand.
+fn write_notation(
+    f: &mut Formatter<'_>,
+    group: &SetGroup,
+    per_side: bool,
+    per_hand: bool,
+) -> std::fmt::Result {
+    write!(
+        f,
+        "{}",
+        format(&group.sets_without_effort(), if per_side { "/side" } else { "" })
+    )?;
+    if let Some(effort) = group.effort() {
+        write!(f, " @ {effort}")?;
+    }
+    if group.weight != WeightScheme::None {
+        write!(f, " @ {}", group.weight)?;
+        if per_hand {
+            write!(f, "/hand")?;
+        }
+    }
+    Ok(())
+}
+
+/// How much lighter each drop of a drop set is than the weight before it, in percent,
+/// unless the set says.
+const DROP_PERCENT: f64 = 20.0;
+
+/// Words that start an attribute of a lift, written after its sets and weight.
+pub const ATTRIBUTES: [&str; 7] =
+    ["progress", "round", "equipment", "warmup", "rest", "tempo", "graduate"];
+
+/// What a lift's name can't have in it without being put in quotes.
+pub const NAME_SEPARATORS: [&str; 6] = ["->", " | ", " + ", " !! ", " !!! ", "="];
+
+/// What a lift is known by, whatever its sets and weight are: its name, ignoring case and
+/// spacing. Its weight and where it is in its progression are kept under it, so they carry
+/// over when its sets change. Lifts of the same name on different days share them, as long as
+/// they have the same sets (see `Lift::id_in()`).
+/// ```
+/// # use yawa::domain::lifting::{Lift, LiftId};
+/// assert_eq!(
+///     Lift::parse("Face pull -> 2x15,1x15-25 @ add5").unwrap().id(),
+///     Lift::parse("Face  Pull -> 3x12 @ add5").unwrap().id()
+/// );
+/// assert_eq!(LiftId::new(" Face Pull").to_string(), "face pull");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LiftId(String);
 
-impl Hash for Lift {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.to_string().hash(state)
+impl LiftId {
+    pub fn new(name: &str) -> Self {
+        LiftId(name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase())
     }
 }
 
-fn format(sets: &Vec<Set>) -> String {
+impl Display for LiftId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn format(sets: &Vec<Set>, suffix: &str) -> String {
     struct Accum<'a> {
         count: i64,
         set: &'a Set,
@@ -31,30 +143,47 @@ fn format(sets: &Vec<Set>) -> String {
         } else {
             accumulated_sets.push(Accum {
                 count: 1,
-                set: &set,
+                set,
             });
         }
     }
     accumulated_sets
         .iter()
-        .map(|x| format!("{}x{}", x.count, x.set))
+        .map(|x| format!("{}x{}{}", x.count, x.set, suffix))
         .collect::<Vec<String>>()
         .join(",")
 }
 
 impl Display for Lift {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if format!("{}", self.weight) != "" {
-            write!(
-                f,
-                "{} -> {} @ {}",
-                self.name,
-                format(&self.sets),
-                self.weight
-            )
-        } else {
-            write!(f, "{} -> {}", self.name, format(&self.sets))
+        write!(f, "{} -> ", quote(&self.name, &NAME_SEPARATORS))?;
+        write_notation(f, &self.top_sets(), self.per_side, self.per_hand)?;
+        for group in &self.back_off {
+            write!(f, ", ")?;
+            write_notation(f, group, self.per_side, self.per_hand)?;
+        }
+        if let Some(progression) = &self.progression {
+            write!(f, " progress {progression}")?;
+        }
+        if let Some(rounding) = &self.rounding {
+            write!(f, " round {rounding}")?;
+        }
+        if let Some(equipment) = &self.equipment {
+            write!(f, " equipment {equipment}")?;
+        }
+        if let Some(warmup) = &self.warmup {
+            write!(f, " warmup {warmup}")?;
+        }
+        if let Some(rest) = &self.rest {
+            write!(f, " rest {rest}")?;
         }
+        if let Some(tempo) = &self.tempo {
+            write!(f, " tempo {tempo}")?;
+        }
+        if let Some(graduation) = &self.graduation {
+            write!(f, " graduate {graduation}")?;
+        }
+        Ok(())
     }
 }
 
@@ -73,39 +202,387 @@ impl Lift {
     /// # use yawa::domain::lifting::Lift;
     /// Lift::parse("Barbell bench press -> 3x5,1x5-6,1x6+ @ 0.8r-10").unwrap();
     /// ```
+    ///
+    /// The top sets can be followed by groups of sets at their own weight, like back-off sets.
+    /// They must be based on a reference weight (or be 'any'), and don't affect progression:
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("Squat -> 1x3+ @ 1r[squat], 3x8 @ 0.8r[squat]").unwrap();
+    /// Lift::parse("Squat -> 1x5 @ 1r, 1x8 @ 0.9r, 1x10 @ 0.8r").unwrap();
+    /// Lift::parse("Squat -> 1x5 @ 1r, 3x8 @ add5").is_err();
+    /// Lift::parse("Squat -> 1x5 @ 1r, 3x8").is_err();
+    /// ```
+    ///
+    /// Sets can have a target for how hard they should feel (see `Effort::parse()`). For the
+    /// weight to follow how hard they actually felt, the lift progresses by RPE:
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("Squat -> 3x5 @ RPE8 @ 1r progress rpe").unwrap();
+    /// Lift::parse("Bench press -> 1x3 @ 2RIR @ 1r[bench], 3x8 @ RPE7 @ 0.8r[bench]").unwrap();
+    /// Lift::parse("Pullup -> 3x8 @ RPE8").unwrap();
+    /// ```
+    ///
+    /// Attributes can follow, like how the lift progresses (see `ProgressionScheme::parse()`):
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("Squat -> 3x5 @ 1r progress linear+10").unwrap();
+    /// ```
+    ///
+    /// or how its weight is rounded (see `Rounding::parse()`):
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("Cable row -> 3x10 @ add5 round 2.5 nearest").unwrap();
+    /// ```
+    ///
+    /// or what it's done with (see `Equipment::parse()`):
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("Dumbbell row -> 3x10 @ add5 equipment dumbbell").unwrap();
+    /// ```
+    ///
+    /// or how to warm up for it (see `WarmupScheme::parse()`):
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("Leg press -> 3x10 @ add20 warmup 50% x10, 75% x5").unwrap();
+    /// ```
+    ///
+    /// or how long to rest between sets and how fast to do each rep (see `Rest::parse()`
+    /// and `Tempo::parse()`):
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("Squat -> 4x3 @ 1.35r rest 3min tempo 3010").unwrap();
+    /// ```
+    ///
+    /// Lifts without a weight to add to can progress their reps or seconds instead, and can
+    /// graduate to a harder variant (see `Graduation::parse()`):
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("Plank -> 1x30s progress time+5").unwrap();
+    /// Lift::parse("Pullup -> 3x5+ progress reps graduate at 12 to Weighted Pullup @ add5 from 10").unwrap();
+    /// ```
+    ///
+    /// Lifts done on each side in turn have their sets marked per side, and lifts done with
+    /// a pair of dumbbells can have their weight marked per hand, rather than in total:
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("Bulgarian split squat -> 3x10/side @ add10").unwrap();
+    /// Lift::parse("Dumbbell press -> 3x10 @ add5/hand equipment dumbbell").unwrap();
+    /// ```
+    ///
+    /// Names with anything in them that could be taken for the rest of the notation, like
+    /// '->' or ' | ', are put in quotes (see `notation::quote()`):
+    /// ```
+    /// # use yawa::domain::lifting::Lift;
+    /// Lift::parse("\"Box jump -> step down\" -> 3x5").unwrap();
+    /// Lift::parse("Box jump -> step down -> 3x5").is_err();
+    /// ```
     pub fn parse(notation: &str) -> Result<Self> {
-        let error = "Cannot parse notation";
-        let name = notation.split("->").next().ok_or(anyhow!(error))?.trim();
-        let rest = notation.split("->").skip(1).next().ok_or(anyhow!(error))?;
-        let reps = if !rest.contains('@') {
-            rest.trim()
-        } else {
-            rest.split("@").next().ok_or(anyhow!(error))?.trim()
-        };
-        let weight = if rest.contains('@') {
-            let a = rest.split("@").skip(1).next().ok_or(anyhow!(error))?.trim();
-            WeightScheme::parse(a)?
-        } else {
-            WeightScheme::None
-        };
+        let (name, sets) = split_once_unquoted(notation, "->").ok_or_else(|| {
+            NotationError::new("A lift needs '->' between its name and its sets", notation)
+                .suggesting(Self::fix_arrow(notation))
+        })?;
+        Self::parse_named(unquote(name)?, sets)
+    }
+
+    /// Parses the sets, weights and attributes of the lift with the name, which has already
+    /// been read from the notation before the '->'.
+    pub fn parse_named(name: String, notation: &str) -> Result<Self> {
+        let (rest, attributes) = Self::split_attributes(notation);
+        let per_side = split_unquoted(&rest, "/side").len() > 1;
+        let per_hand = split_unquoted(&rest, "/hand").len() > 1;
+        let rest = split_unquoted(&rest, "/side").concat();
+        let rest = split_unquoted(&rest, "/hand").concat();
+        let rest = rest.as_str();
+        let mut progression = None;
+        let mut rounding = None;
+        let mut equipment = None;
+        let mut warmup = None;
+        let mut rest_between_sets = None;
+        let mut tempo = None;
+        let mut graduation = None;
+        attributes
+            .into_iter()
+            .try_for_each(|(attribute, value)| -> Result<()> {
+                let error = format!("Cannot parse {attribute}");
+                match attribute {
+                    "progress" => {
+                        progression = Some(ProgressionScheme::parse(&value).notation(&error, &value)?)
+                    }
+                    "round" => rounding = Some(Rounding::parse(&value).notation(&error, &value)?),
+                    "equipment" => {
+                        equipment = Some(Equipment::parse(&value).notation(&error, &value)?)
+                    }
+                    "warmup" => warmup = Some(WarmupScheme::parse(&value).notation(&error, &value)?),
+                    "rest" => rest_between_sets = Some(Rest::parse(&value).notation(&error, &value)?),
+                    "tempo" => tempo = Some(Tempo::parse(&value).notation(&error, &value)?),
+                    "graduate" => {
+                        graduation = Some(Graduation::parse(&value).notation(&error, &value)?)
+                    }
+                    _ => return Err(NotationError::new("Unknown attribute", attribute).into()),
+                }
+                Ok(())
+            })?;
+        let mut groups = Self::parse_set_groups(rest)
+            .map_err(|error| Self::misspelt_attribute(rest).map_or(error, Into::into))?
+            .into_iter();
+        let top = groups
+            .next()
+            .ok_or(NotationError::new("A lift needs sets, like '3x5'", notation))?;
+        let back_off: Vec<SetGroup> = groups.collect();
+        if let Some(group) = back_off.iter().find(|group| {
+            !matches!(group.weight, WeightScheme::BasedOnReference { .. } | WeightScheme::Any)
+        }) {
+            return Err(NotationError::new(
+                "Back-off sets must be based on a reference weight, or be 'any'",
+                &group.weight.to_string(),
+            )
+            .into());
+        }
         Ok(Lift {
-            name: name.to_string(),
-            sets: Self::parse_sets(reps)?,
-            weight,
+            name,
+            sets: top.sets,
+            weight: top.weight,
+            back_off,
+            progression,
+            rounding,
+            equipment,
+            warmup,
+            rest: rest_between_sets,
+            tempo,
+            graduation,
+            per_side,
+            per_hand,
         })
     }
 
+    /// What the notation probably meant, if it parses with an arrow in place of what's
+    /// between the name and the sets.
+    fn fix_arrow(notation: &str) -> Option<String> {
+        ["=>", " - ", " > ", ": "]
+            .iter()
+            .filter(|arrow| notation.contains(*arrow))
+            .map(|arrow| notation.replacen(arrow, " -> ", 1).replace("  ", " "))
+            .find(|fix| Self::parse(fix).is_ok())
+    }
+
+    /// A word among the sets that looks like a misspelt attribute, like 'progres'.
+    fn misspelt_attribute(notation: &str) -> Option<NotationError> {
+        notation.split_whitespace().find_map(|word| {
+            let attribute = suggest(word, &ATTRIBUTES)?;
+            Some(
+                NotationError::new("Unknown attribute", word)
+                    .suggesting(Some(attribute.to_string())),
+            )
+        })
+    }
+
+    /// Splits notation like '3x5 @ 1r progress linear' into '3x5 @ 1r' and its attributes.
+    fn split_attributes(notation: &str) -> (String, Vec<(&'static str, String)>) {
+        let mut rest: Vec<&str> = Vec::new();
+        let mut attributes: Vec<(&'static str, Vec<&str>)> = Vec::new();
+        words(notation).into_iter().for_each(|word| {
+            if let Some(attribute) = ATTRIBUTES.iter().find(|it| **it == word) {
+                attributes.push((attribute, Vec::new()));
+            } else if let Some((_, value)) = attributes.last_mut() {
+                value.push(word);
+            } else {
+                rest.push(word);
+            }
+        });
+        (
+            rest.join(" "),
+            attributes
+                .into_iter()
+                .map(|(attribute, value)| (attribute, value.join(" ")))
+                .collect(),
+        )
+    }
+
+    /// notation is like '4x3,1x3+ @ 1r, 3x8 @ 0.8r', or just '3x7+' for sets without a weight.
+    /// Each group can have an effort target for its sets (see `Effort::parse()`), like
+    /// '3x5 @ RPE8 @ 1r'.
+    fn parse_set_groups(notation: &str) -> Result<Vec<SetGroup>> {
+        let mut groups = Vec::new();
+        let mut sets: Vec<&str> = Vec::new();
+        split_unquoted(notation, ",").into_iter().try_for_each(|part| -> Result<()> {
+            let mut split = split_unquoted(part, "@").into_iter();
+            sets.push(split.next().unwrap_or_default().trim());
+            let targets: Vec<&str> = split.map(|it| it.trim()).collect();
+            if targets.is_empty() {
+                return Ok(());
+            }
+            let mut weight = WeightScheme::None;
+            let mut effort = None;
+            targets.into_iter().try_for_each(|target| -> Result<()> {
+                match Effort::parse(target) {
+                    Ok(target) if effort.is_none() => effort = Some(target),
+                    _ if weight == WeightScheme::None => weight = WeightScheme::parse(target)?,
+                    _ => return Err(NotationError::new("Cannot parse sets", part).into()),
+                }
+                Ok(())
+            })?;
+            groups.push(SetGroup {
+                sets: Self::parse_sets(&sets.join(","))?
+                    .iter()
+                    .map(|set| set.with_effort(effort))
+                    .collect(),
+                weight,
+            });
+            sets.clear();
+            Ok(())
+        })?;
+        if !sets.is_empty() {
+            if !groups.is_empty() {
+                return Err(NotationError::new(
+                    "Sets after the top sets need a weight",
+                    &sets.join(","),
+                )
+                .into());
+            }
+            groups.push(SetGroup {
+                sets: Self::parse_sets(&sets.join(","))?,
+                weight: WeightScheme::None,
+            });
+        }
+        Ok(groups)
+    }
+
+    /// The top sets, with their weight.
+    pub fn top_sets(&self) -> SetGroup {
+        SetGroup {
+            sets: self.sets.clone(),
+            weight: self.weight.clone(),
+        }
+    }
+
+    /// How hard the top sets should feel, if the lift has a target.
+    pub fn effort(&self) -> Option<Effort> {
+        self.top_sets().effort()
+    }
+
+    /// The progression this lift asks for, if it asks for one.
+    pub fn own_progression(&self) -> Option<ProgressionScheme> {
+        self.progression.clone()
+    }
+
+    /// What the lift is known by, wherever it is.
+    pub fn id(&self) -> LiftId {
+        LiftId::new(&self.name)
+    }
+
+    /// What the lift's weight and progression are kept under, in a program of these days: its
+    /// `id()`, unless a lift of the same name on another day has different sets, like a heavy
+    /// and a light day's squats. Then each keeps its own, under the name of the first day it's
+    /// on as well.
+    /// ```
+    /// # use yawa::domain::day::Day;
+    /// # use yawa::domain::lifting::Lift;
+    /// let days = [
+    ///     Day::parse("Heavy | Squat -> 5x5 @ add5 | Curl -> 3x10 @ add5").unwrap(),
+    ///     Day::parse("Light | Squat -> 3x8 @ add5 | Curl -> 3x10 @ add5").unwrap(),
+    /// ];
+    /// assert_eq!(days[1].lifts[0].id_in(&days).to_string(), "light | squat");
+    /// assert_eq!(days[1].lifts[1].id_in(&days).to_string(), "curl");
+    /// ```
+    pub fn id_in(&self, days: &[Day]) -> LiftId {
+        let id = self.id();
+        let same_named = || {
+            days.iter()
+                .flat_map(|day| day.lifts.iter().map(move |lift| (day, lift)))
+                .filter(|(_, lift)| lift.id() == id)
+        };
+        if same_named().all(|(_, lift)| lift.all_sets() == self.all_sets()) {
+            return id;
+        }
+        match same_named().find(|(_, lift)| lift.all_sets() == self.all_sets()) {
+            Some((day, _)) => LiftId::new(&format!("{} | {}", day.name, self.name)),
+            None => id,
+        }
+    }
+
+    /// Whether the lift gets harder over time: its weight goes up, along with its reference
+    /// weight or from its previous weight, or it has a progression of its own.
+    pub fn progresses(&self) -> bool {
+        matches!(
+            self.weight,
+            WeightScheme::BasedOnReference { .. } | WeightScheme::LinearBasedOnPrevious { .. }
+        ) || self.own_progression().is_some()
+    }
+
+    /// Every set of the lift, in order: the top sets, then the back-off sets.
+    pub fn all_sets(&self) -> Vec<Set> {
+        self.back_off
+            .iter()
+            .fold(self.sets.clone(), |sets, group| [sets, group.sets.clone()].concat())
+    }
+
+    /// How long each set keeps the muscles under tension, going by the lift's tempo and the
+    /// fewest reps the set asks for. Empty if the lift has no tempo.
+    pub fn time_under_tension(&self) -> Vec<Duration> {
+        match &self.tempo {
+            Some(tempo) => self
+                .all_sets()
+                .iter()
+                .map(|set| match set.without_effort() {
+                    Set::Time { duration } => *duration,
+                    _ => tempo.time_under_tension(set.minimum_reps()),
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// What the lift is done with: what it says, or bodyweight for lifts with a bodyweight
+    /// scheme, or a barbell.
+    pub fn done_with(&self) -> Equipment {
+        match (self.equipment, &self.weight) {
+            (Some(equipment), _) => equipment,
+            (None, WeightScheme::Bodyweight { .. }) => Equipment::Bodyweight,
+            (None, _) => Equipment::default(),
+        }
+    }
+
+    /// The same lift, with the weight of its top sets scaled by the factor, along with the
+    /// back-off sets based on the same reference weight.
+    pub fn scaled(&self, factor: f64) -> Self {
+        let reference = |scheme: &WeightScheme| match scheme {
+            WeightScheme::BasedOnReference { reference, .. } => Some(reference.clone()),
+            _ => None,
+        };
+        Lift {
+            weight: self.weight.scaled(factor),
+            back_off: self
+                .back_off
+                .iter()
+                .map(|group| match reference(&group.weight) == reference(&self.weight) {
+                    true => SetGroup {
+                        weight: group.weight.scaled(factor),
+                        ..group.clone()
+                    },
+                    false => group.clone(),
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+
     /// notation is like '2x3,1x3+'
     fn parse_sets(notation: &str) -> Result<Vec<Set>> {
-        let error = "Cannot parse sets notation";
         let mut vec = Vec::new();
         notation
             .split(',')
             .try_for_each(|sets_n_reps| -> Result<()> {
                 // sets_n_reps looks like '2x3+'
-                let mut split = sets_n_reps.split('x');
-                let times: i64 = split.next().ok_or(anyhow!(error))?.parse()?;
-                let reps = split.next().ok_or(anyhow!(error))?;
+                let (times, reps) = sets_n_reps.split_once('x').ok_or_else(|| {
+                    let fix = match sets_n_reps.split_once(['X', '*']) {
+                        Some((times, reps)) => format!("{}x{}", times.trim(), reps.trim()),
+                        None => format!("1x{}", sets_n_reps.trim()),
+                    };
+                    NotationError::new("Sets need to say how many there are, like '3x5'", sets_n_reps)
+                        .suggesting(Self::parse_sets(&fix).is_ok().then_some(fix))
+                })?;
+                let times = count(times.trim())
+                    .map_err(|_| NotationError::new("Cannot parse how many sets", times))?;
                 for _ in 0..times {
                     let b = Set::parse(reps)?;
                     vec.push(b);
@@ -119,13 +596,48 @@ impl Lift {
 #[derive(Clone, Debug, PartialEq)]
 pub struct LiftAttempt {
     pub lift: Lift,
-    pub weight: Option<usize>,
+    pub weight: Option<f64>,
+    /// How the weight to put on the bar is worked out from `weight`.
+    pub rounding: Rounding,
+    pub unit: Unit,
+    /// Anything worth knowing about this attempt, like where its progression is at.
+    pub note: Option<String>,
+    /// How to load the bar, for barbell lifts.
+    pub plates: Option<PlateLoading>,
+    /// The sets to ramp up with before the working sets, lightest first.
+    pub warmups: Vec<WarmupSet>,
+    /// Like `weight`, for each of the lift's back-off set groups.
+    pub back_off_weights: Vec<Option<f64>>,
+    /// Which of the day's supersets this lift is done in, if any.
+    pub superset: Option<usize>,
+    /// The lifter's bodyweight, if it has been logged.
+    pub bodyweight: Option<f64>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum LiftAttemptResult {
     NotCompleted,
     Completed { completed_maximum_reps: bool },
+    /// What was actually done: the reps achieved in each set (seconds, for timed sets, rounds,
+    /// for sets with a time cap, or 1 for distances and calories that were done), the weight used
+    /// for the top sets and for each back-off set group, and how hard it felt.
+    /// Sets done in parts, like drop sets, count all their reps, and have the reps of each part
+    /// in `parts`, by the set's index: empty for sets done in one go, and altogether empty
+    /// when no set was done in parts.
+    /// Distances and calories have how long they took in `time_taken`, by the set's index,
+    /// which is altogether empty when no set was timed.
+    /// Lifts done per side have the reps of each part of each set on the left, then the right,
+    /// in `sides`, by the set's index, and count the weaker side in `reps` and `parts`.
+    /// It's altogether empty for lifts not done per side.
+    Performed {
+        reps: Vec<usize>,
+        parts: Vec<Vec<usize>>,
+        sides: Vec<[Vec<usize>; 2]>,
+        time_taken: Vec<Option<Duration>>,
+        weight: Option<f64>,
+        back_off_weights: Vec<Option<f64>>,
+        rpe: Option<f64>,
+    },
 }
 
 impl Display for LiftAttemptResult {
@@ -138,6 +650,56 @@ impl Display for LiftAttemptResult {
                 true => write!(f, "Completed+MaxReps"),
                 false => write!(f, "Completed"),
             },
+            LiftAttemptResult::Performed {
+                reps,
+                parts,
+                sides,
+                time_taken,
+                weight,
+                back_off_weights,
+                rpe,
+            } => {
+                let join = |parts: &[usize]| {
+                    parts
+                        .iter()
+                        .map(|it| it.to_string())
+                        .collect::<Vec<String>>()
+                        .join("+")
+                };
+                let reps = reps
+                    .iter()
+                    .enumerate()
+                    .map(|(index, reps)| {
+                        match (sides.get(index), parts.get(index).filter(|it| !it.is_empty())) {
+                            (Some([left, right]), _) => format!("{}/{}", join(left), join(right)),
+                            (None, Some(parts)) => join(parts),
+                            (None, None) => reps.to_string(),
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(",");
+                write!(f, "Performed {reps}")?;
+                if !time_taken.is_empty() {
+                    let times = time_taken
+                        .iter()
+                        .map(|it| it.map_or("-".to_string(), display_duration))
+                        .collect::<Vec<String>>()
+                        .join(",");
+                    write!(f, " in {times}")?;
+                }
+                if weight.is_some() || back_off_weights.iter().any(Option::is_some) {
+                    let weights = std::iter::once(weight)
+                        .chain(back_off_weights)
+                        .map(|it| it.map_or("?".to_string(), |it| it.to_string()))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    write!(f, " @ {weights}")?;
+                }
+                if let Some(rpe) = rpe {
+                    write!(f, " RPE {rpe}")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -149,6 +711,13 @@ impl LiftAttemptResult {
     /// LiftAttemptResult::parse("NotCompleted").unwrap();
     /// LiftAttemptResult::parse("Completed").unwrap();
     /// LiftAttemptResult::parse("Completed+MaxReps").unwrap();
+    /// LiftAttemptResult::parse("Performed 3,3,3,3,8 @ 205 RPE 8.5").unwrap();
+    /// LiftAttemptResult::parse("Performed 15,15,22").unwrap();
+    /// LiftAttemptResult::parse("Performed 10,8+3+2+2 @ 100").unwrap();
+    /// LiftAttemptResult::parse("Performed 5,8,6 @ 200, 160").unwrap();
+    /// LiftAttemptResult::parse("Performed 1,1,5 in 35s,1min2s,- @ 90").unwrap();
+    /// LiftAttemptResult::parse("Performed 10/9,10/10 @ 30").unwrap();
+    /// LiftAttemptResult::parse("Performed 10/9,10 @ 30").is_err();
     /// LiftAttemptResult::parse("AnythingElse").is_err();
     /// ```
     pub fn parse(notation: &str) -> Result<Self> {
@@ -160,50 +729,448 @@ impl LiftAttemptResult {
             "Completed+MaxReps" => Ok(LiftAttemptResult::Completed {
                 completed_maximum_reps: true,
             }),
-            _ => Err(anyhow!("Cannot parse notation")),
+            _ => {
+                let performance = notation
+                    .strip_prefix("Performed ")
+                    .ok_or(anyhow!("Cannot parse notation"))?;
+                let (performance, rpe) = match performance.split_once(" RPE ") {
+                    Some((performance, rpe)) => (performance, Some(rpe.trim().parse()?)),
+                    None => (performance, None),
+                };
+                let (reps, weights) = match performance.split_once(" @ ") {
+                    Some((reps, weights)) => (
+                        reps,
+                        weights
+                            .split(',')
+                            .map(|it| match it.trim() {
+                                "?" => Ok(None),
+                                weight => weight.parse().map(Some),
+                            })
+                            .collect::<Result<Vec<Option<f64>>, _>>()?,
+                    ),
+                    None => (performance, Vec::new()),
+                };
+                let (reps, time_taken) = match reps.split_once(" in ") {
+                    Some((reps, times)) => (
+                        reps,
+                        times
+                            .split(',')
+                            .map(|it| match it.trim() {
+                                "-" => Ok(None),
+                                time => parse_duration(time).map(Some),
+                            })
+                            .collect::<Result<Vec<Option<Duration>>>>()?,
+                    ),
+                    None => (reps, Vec::new()),
+                };
+                // The parts of each set, or of each side of it.
+                let sets = reps
+                    .split(',')
+                    .map(|set| {
+                        set.split('/')
+                            .map(|side| {
+                                side.split('+')
+                                    .map(|it| it.trim().parse())
+                                    .collect::<Result<Vec<usize>, _>>()
+                            })
+                            .collect::<Result<Vec<Vec<usize>>, _>>()
+                    })
+                    .collect::<Result<Vec<Vec<Vec<usize>>>, _>>()?;
+                let weight = weights.first().copied().flatten();
+                let performed = if sets.iter().all(|it| it.len() == 1) {
+                    LiftAttemptResult::performed_in_parts(
+                        sets.into_iter().flatten().collect(),
+                        weight,
+                        rpe,
+                    )
+                } else if sets.iter().all(|it| it.len() == 2) {
+                    LiftAttemptResult::performed_per_side(
+                        sets.into_iter().map(|it| [it[0].clone(), it[1].clone()]).collect(),
+                        weight,
+                        rpe,
+                    )
+                } else {
+                    return Err(anyhow!("Cannot parse notation: every set is one side, or both"));
+                };
+                Ok(performed
+                    .with_back_off_weights(weights.into_iter().skip(1).collect())
+                    .with_time_taken(time_taken))
+            }
+        }
+    }
+
+    /// What was done, from the reps of each set, with every set done in one go.
+    pub fn performed(reps: Vec<usize>, weight: Option<f64>, rpe: Option<f64>) -> Self {
+        LiftAttemptResult::Performed {
+            reps,
+            parts: Vec::new(),
+            sides: Vec::new(),
+            time_taken: Vec::new(),
+            weight,
+            back_off_weights: Vec::new(),
+            rpe,
         }
     }
-}
 
-fn round_up_to_nearest5(input: usize) -> usize{
-    let remainder = input % 5;
-    return if remainder > 0 {
-        input + (5 - remainder)
-    } else {
-        input
+    /// What was done, from the reps of each part of each set. Sets done in one go have
+    /// a single part.
+    pub fn performed_in_parts(sets: Vec<Vec<usize>>, weight: Option<f64>, rpe: Option<f64>) -> Self {
+        let parts = match sets.iter().any(|it| it.len() > 1) {
+            true => sets
+                .iter()
+                .map(|it| if it.len() > 1 { it.clone() } else { Vec::new() })
+                .collect(),
+            false => Vec::new(),
+        };
+        LiftAttemptResult::Performed {
+            reps: sets.iter().map(|it| it.iter().sum()).collect(),
+            parts,
+            sides: Vec::new(),
+            time_taken: Vec::new(),
+            weight,
+            back_off_weights: Vec::new(),
+            rpe,
+        }
     }
-}
 
-impl Display for LiftAttempt {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self.lift.weight {
-            WeightScheme::None => {
-                write!(f, "{} -> {}", self.lift.name, format(&self.lift.sets))
+    /// What was done on each side, from the reps of each part of each set on the left, then
+    /// the right. It counts as what the weaker side did in each part.
+    pub fn performed_per_side(
+        sides: Vec<[Vec<usize>; 2]>,
+        weight: Option<f64>,
+        rpe: Option<f64>,
+    ) -> Self {
+        let weaker = sides
+            .iter()
+            .map(|[left, right]| left.iter().zip(right).map(|(left, right)| *left.min(right)).collect())
+            .collect();
+        match LiftAttemptResult::performed_in_parts(weaker, weight, rpe) {
+            LiftAttemptResult::Performed { reps, parts, weight, rpe, .. } => {
+                LiftAttemptResult::Performed {
+                    reps,
+                    parts,
+                    sides,
+                    time_taken: Vec::new(),
+                    weight,
+                    back_off_weights: Vec::new(),
+                    rpe,
+                }
             }
-            WeightScheme::BasedOnReference { multiplier, offset } => {
-                let weight = (multiplier * (self.weight.unwrap() as f64)) + (offset as f64);
-                write!(
-                    f,
-                    "{} -> {} @ {}",
-                    self.lift.name,
-                    format(&self.lift.sets),
-                    round_up_to_nearest5(weight.ceil() as usize)
-                )
+            other => other,
+        }
+    }
+
+    /// The same result, with the weight used for each of the lift's back-off set groups.
+    pub fn with_back_off_weights(self, weights: Vec<Option<f64>>) -> Self {
+        match self {
+            LiftAttemptResult::Performed { reps, parts, sides, time_taken, weight, rpe, .. } => {
+                LiftAttemptResult::Performed {
+                    reps,
+                    parts,
+                    sides,
+                    time_taken,
+                    weight,
+                    back_off_weights: weights,
+                    rpe,
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// The same result, with how long each set took, by the set's index: `None` for sets that
+    /// weren't timed.
+    pub fn with_time_taken(self, times: Vec<Option<Duration>>) -> Self {
+        let times = match times.iter().any(Option::is_some) {
+            true => times,
+            false => Vec::new(),
+        };
+        match self {
+            LiftAttemptResult::Performed { reps, parts, sides, weight, back_off_weights, rpe, .. } => {
+                LiftAttemptResult::Performed {
+                    reps,
+                    parts,
+                    sides,
+                    time_taken: times,
+                    weight,
+                    back_off_weights,
+                    rpe,
+                }
             }
-            WeightScheme::Any => {
-                write!(f, "{} -> {} @ any", self.lift.name, format(&self.lift.sets))
+            other => other,
+        }
+    }
+
+    /// Whether every set of the lift reached at least its minimum reps.
+    pub fn is_completed(&self, lift: &Lift) -> bool {
+        match self {
+            LiftAttemptResult::NotCompleted => false,
+            LiftAttemptResult::Completed { .. } => true,
+            LiftAttemptResult::Performed { reps, parts, .. } => {
+                Self::every_set(lift, reps, |set, reps| reps >= set.minimum_reps())
+                    && Self::every_part(lift, parts)
             }
-            WeightScheme::LinearBasedOnPrevious { .. } => write!(
-                f,
-                "{} -> {} @ {}",
-                self.lift.name,
-                format(&self.lift.sets),
-                if self.weight.is_some() {
-                    round_up_to_nearest5(self.weight.unwrap()).to_string()
-                } else {
-                    "any".to_string()
+        }
+    }
+
+    /// Whether every set of the lift reached its maximum reps.
+    pub fn completed_maximum_reps(&self, lift: &Lift) -> bool {
+        match self {
+            LiftAttemptResult::NotCompleted => false,
+            LiftAttemptResult::Completed {
+                completed_maximum_reps,
+            } => *completed_maximum_reps,
+            LiftAttemptResult::Performed { reps, parts, .. } => {
+                Self::every_set(lift, reps, |set, reps| reps >= set.maximum_reps())
+                    && Self::every_part(lift, parts)
+            }
+        }
+    }
+
+    /// Whether every top set of the lift got at least this many reps.
+    pub fn reached_reps(&self, lift: &Lift, target: usize) -> bool {
+        match self {
+            LiftAttemptResult::NotCompleted => false,
+            LiftAttemptResult::Completed {
+                completed_maximum_reps,
+            } => lift.sets.iter().all(|set| match completed_maximum_reps {
+                true => set.maximum_reps() >= target,
+                false => set.minimum_reps() >= target,
+            }),
+            LiftAttemptResult::Performed { reps, .. } => {
+                Self::every_set(lift, reps, |_, reps| reps >= target)
+            }
+        }
+    }
+
+    fn every_set(lift: &Lift, reps: &[usize], predicate: impl Fn(&Set, usize) -> bool) -> bool {
+        lift.sets
+            .iter()
+            .enumerate()
+            .all(|(index, set)| predicate(set, reps.get(index).copied().unwrap_or(0)))
+    }
+
+    /// Whether each part recorded of the sets done in parts reached its reps, like every cluster
+    /// of a cluster set. Parts done to failure count whatever they got.
+    fn every_part(lift: &Lift, parts: &[Vec<usize>]) -> bool {
+        lift.sets
+            .iter()
+            .zip(parts)
+            .filter(|(_, done)| !done.is_empty())
+            .all(|(set, done)| {
+                set.parts().iter().enumerate().all(|(index, target)| {
+                    target.is_none_or(|target| done.get(index).copied().unwrap_or(0) >= target)
+                })
+            })
+    }
+
+    /// The weight actually used, if it was recorded.
+    pub fn weight_used(&self) -> Option<f64> {
+        match self {
+            LiftAttemptResult::Performed { weight, .. } => *weight,
+            _ => None,
+        }
+    }
+}
+
+impl LiftAttempt {
+    /// The weight to put on the bar for this attempt, if there is one.
+    pub fn prescribed_weight(&self) -> Option<f64> {
+        self.prescribe(&self.lift.weight, self.weight)
+    }
+
+    /// The weight to put on the bar for each of the lift's back-off set groups.
+    pub fn prescribed_back_off_weights(&self) -> Vec<Option<f64>> {
+        self.lift
+            .back_off
+            .iter()
+            .enumerate()
+            .map(|(index, group)| {
+                self.prescribe(&group.weight, self.back_off_weights.get(index).copied().flatten())
+            })
+            .collect()
+    }
+
+    /// For bodyweight lifts this is the weight added, which goes below zero for assistance.
+    /// Other lifts can't be lighter than nothing.
+    fn prescribe(&self, scheme: &WeightScheme, weight: Option<f64>) -> Option<f64> {
+        match scheme {
+            WeightScheme::BasedOnReference {
+                multiplier,
+                offset,
+                ..
+            } => {
+                let weight = (multiplier * weight?) + offset;
+                match self.lift.done_with() {
+                    Equipment::Bodyweight => Some(self.rounding.round(weight)),
+                    _ => Some(self.rounding.round(weight.max(0.0))),
                 }
+            }
+            WeightScheme::Bodyweight { added } => Some(*added),
+            WeightScheme::LinearBasedOnPrevious { .. } => {
+                weight.map(|weight| self.rounding.round(weight))
+            }
+            WeightScheme::Any | WeightScheme::None => None,
+        }
+    }
+
+    /// The weight to put on the bar for each set, in the order of `Lift::all_sets()`.
+    pub fn prescribed_set_weights(&self) -> Vec<Option<f64>> {
+        let groups = [vec![self.lift.top_sets()], self.lift.back_off.clone()].concat();
+        let weights = [vec![self.prescribed_weight()], self.prescribed_back_off_weights()].concat();
+        groups
+            .iter()
+            .zip(weights)
+            .flat_map(|(group, weight)| vec![weight; group.sets.len()])
+            .collect()
+    }
+
+    /// The weights to drop to in the set at the index, each lighter than the one before it by
+    /// the set's percentage (a fifth, if it doesn't say), rounded down. Empty unless it's a drop
+    /// set with a weight to drop from.
+    pub fn drop_weights(&self, set_index: usize) -> Vec<f64> {
+        let set = self
+            .lift
+            .all_sets()
+            .get(set_index)
+            .map(|it| it.without_effort().clone());
+        let weight = self.prescribed_set_weights().get(set_index).copied().flatten();
+        match (set, weight) {
+            (Some(Set::Drop { drops, percent, .. }), Some(weight)) if weight > 0.0 => {
+                let lighter = 1.0 - percent.unwrap_or(DROP_PERCENT) / 100.0;
+                (1..=drops)
+                    .map(|drop| self.rounding.down().round(weight * lighter.powi(drop as i32)))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// What was lifted in total: each set's reps times its weight, counting both sides of
+    /// lifts done per side (what each side did, when it was recorded), and both hands of lifts
+    /// done per hand. Sets that aren't counted in reps, or have no weight, add nothing.
+    pub fn volume(&self, result: &LiftAttemptResult) -> f64 {
+        let sets = self.lift.all_sets();
+        let sides = if self.lift.per_side { 2 } else { 1 };
+        let (reps, used): (Vec<usize>, Vec<Option<f64>>) = match result {
+            LiftAttemptResult::NotCompleted => return 0.0,
+            LiftAttemptResult::Completed {
+                completed_maximum_reps,
+            } => (
+                sets.iter()
+                    .map(|set| match completed_maximum_reps {
+                        true => set.maximum_reps() * sides,
+                        false => set.minimum_reps() * sides,
+                    })
+                    .collect(),
+                Vec::new(),
+            ),
+            LiftAttemptResult::Performed {
+                reps,
+                sides: done,
+                weight,
+                back_off_weights,
+                ..
+            } => (
+                match done.is_empty() {
+                    true => reps.iter().map(|it| it * sides).collect(),
+                    false => done
+                        .iter()
+                        .map(|[left, right]| left.iter().sum::<usize>() + right.iter().sum::<usize>())
+                        .collect(),
+                },
+                [vec![*weight], back_off_weights.clone()].concat(),
             ),
+        };
+        // What was recorded for each group, spread over its sets.
+        let used: Vec<Option<f64>> = [vec![self.lift.top_sets()], self.lift.back_off.clone()]
+            .concat()
+            .iter()
+            .enumerate()
+            .flat_map(|(index, group)| vec![used.get(index).copied().flatten(); group.sets.len()])
+            .collect();
+        let hands = if self.lift.per_hand { 2.0 } else { 1.0 };
+        sets.iter()
+            .zip(self.prescribed_set_weights())
+            .zip(reps)
+            .enumerate()
+            .filter(|(_, ((set, _), _))| {
+                !matches!(
+                    set.without_effort(),
+                    Set::Time { .. } | Set::Distance { .. } | Set::Calories { .. } | Set::Rounds { .. }
+                )
+            })
+            .map(|(index, ((_, weight), reps))| {
+                let weight = used.get(index).copied().flatten().or(weight);
+                weight.unwrap_or(0.0).max(0.0) * reps as f64 * hands
+            })
+            .sum()
+    }
+
+    /// Everything being lifted, for bodyweight lifts when the bodyweight is known:
+    /// the bodyweight, plus what's added to it.
+    pub fn effective_load(&self) -> Option<f64> {
+        match self.lift.done_with() {
+            Equipment::Bodyweight => Some(self.bodyweight? + self.prescribed_weight().unwrap_or(0.0)),
+            _ => None,
+        }
+    }
+}
+
+/// What was lifted in a whole workout: the volume of each attempt with its result
+/// (see `LiftAttempt::volume()`).
+pub fn workout_volume(attempts: &[LiftAttempt], results: &[LiftAttemptResult]) -> f64 {
+    attempts
+        .iter()
+        .zip(results)
+        .map(|(attempt, result)| attempt.volume(result))
+        .sum()
+}
+
+impl LiftAttempt {
+    fn write_group(
+        &self,
+        f: &mut Formatter<'_>,
+        group: &SetGroup,
+        weight: Option<f64>,
+    ) -> std::fmt::Result {
+        write!(f, "{}", format(&group.sets_without_effort(), ""))?;
+        if self.lift.per_side {
+            write!(f, " per side")?;
+        }
+        if let Some(effort) = group.effort() {
+            write!(f, " @ {effort}")?;
+        }
+        let per_hand = if self.lift.per_hand { " per hand" } else { "" };
+        match (&group.weight, weight) {
+            (WeightScheme::None, _) => Ok(()),
+            (_, Some(weight)) if self.lift.done_with() == Equipment::Bodyweight => {
+                match weight == 0.0 {
+                    true => write!(f, " @ bw"),
+                    false => write!(f, " @ {} {}", bodyweight_plus(weight), self.unit),
+                }
+            }
+            (_, Some(weight)) => write!(f, " @ {} {}{}", weight, self.unit, per_hand),
+            (_, None) => write!(f, " @ any"),
+        }
+    }
+}
+
+impl Display for LiftAttempt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> ", self.lift.name)?;
+        self.write_group(f, &self.lift.top_sets(), self.prescribed_weight())?;
+        self.lift
+            .back_off
+            .iter()
+            .zip(self.prescribed_back_off_weights())
+            .try_for_each(|(group, weight)| {
+                write!(f, ", ")?;
+                self.write_group(f, group, weight)
+            })?;
+        match &self.note {
+            Some(note) => write!(f, " ({note})"),
+            None => Ok(()),
         }
     }
 }
@@ -212,6 +1179,22 @@ impl Display for LiftAttempt {
 mod tests {
     use crate::domain::lifting::*;
 
+    /// An attempt at the lift with the weight, shown in pounds and rounded the default way.
+    fn attempt(lift: Lift, weight: Option<f64>) -> LiftAttempt {
+        LiftAttempt {
+            lift,
+            weight,
+            rounding: Default::default(),
+            unit: Unit::Lb,
+            note: None,
+            plates: None,
+            warmups: vec![],
+            back_off_weights: vec![],
+            superset: None,
+            bodyweight: None,
+        }
+    }
+
     #[test]
     fn can_serialize_lift_attempt_result() {
         assert_eq!("NotCompleted", LiftAttemptResult::NotCompleted.to_string());
@@ -231,6 +1214,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_serialize_performed_lift_attempt_result() {
+        assert_eq!(
+            LiftAttemptResult::performed(vec![3, 3, 3, 3, 8], Some(205.0), Some(8.5))
+            .to_string(),
+            "Performed 3,3,3,3,8 @ 205 RPE 8.5"
+        );
+        assert_eq!(
+            LiftAttemptResult::performed(vec![15, 15, 22], None, None)
+            .to_string(),
+            "Performed 15,15,22"
+        );
+    }
+
+    #[test]
+    fn can_parse_performed_lift_attempt_result() {
+        assert_eq!(
+            LiftAttemptResult::parse("Performed 3,3,3,3,8 @ 205 RPE 8.5").unwrap(),
+            LiftAttemptResult::performed(vec![3, 3, 3, 3, 8], Some(205.0), Some(8.5))
+        );
+        assert_eq!(
+            LiftAttemptResult::parse("Performed 10 RPE 9").unwrap(),
+            LiftAttemptResult::performed(vec![10], None, Some(9.0))
+        );
+        let back_off = LiftAttemptResult::parse("Performed 5,8,6 @ 200, 160").unwrap();
+        assert_eq!(
+            back_off,
+            LiftAttemptResult::performed(vec![5, 8, 6], Some(200.0), None)
+                .with_back_off_weights(vec![Some(160.0)])
+        );
+        assert_eq!(back_off.to_string(), "Performed 5,8,6 @ 200, 160");
+        assert!(LiftAttemptResult::parse("Performed three").is_err());
+    }
+
+    #[test]
+    fn performed_results_are_judged_against_sets() {
+        let lift = Lift::parse("Face Pull -> 2x15,1x15-25 @ add20").unwrap();
+        let performed = |reps: Vec<usize>| LiftAttemptResult::performed(reps, None, None);
+        assert!(!performed(vec![15, 14, 20]).is_completed(&lift));
+        assert!(performed(vec![15, 15, 20]).is_completed(&lift));
+        assert!(!performed(vec![15, 15, 20]).completed_maximum_reps(&lift));
+        assert!(performed(vec![15, 15, 25]).completed_maximum_reps(&lift));
+        assert!(!performed(vec![15, 15]).is_completed(&lift));
+
+        let amrap = Lift::parse("Squat -> 4x3,1x3+ @ 1r").unwrap();
+        assert!(performed(vec![3, 3, 3, 3, 12]).completed_maximum_reps(&amrap));
+        assert!(!performed(vec![3, 3, 3, 2, 12]).is_completed(&amrap));
+    }
+
     #[test]
     fn can_parse_lift_attempt_result() {
         assert_eq!(
@@ -253,74 +1285,186 @@ mod tests {
 
     #[test]
     fn lift_attempts_round() {
+        let doobee_doos = |notation: &str| Lift::parse(notation).unwrap();
+        assert_eq!(
+            attempt(doobee_doos("Doobee doos -> 3x5 @ add20"), Some(22.0)).to_string(),
+            "Doobee doos -> 3x5 @ 25 lb"
+        );
+        assert_eq!(
+            attempt(doobee_doos("Doobee doos -> 3x5 @ 0.222r"), Some(100.0)).to_string(),
+            "Doobee doos -> 3x5 @ 25 lb"
+        );
         assert_eq!(
-            LiftAttempt{
-                lift: Lift::parse("Doobee doos -> 3x5 @ add20").unwrap(),
-                weight: Some(22)
-            }.to_string(),
-            "Doobee doos -> 3x5 @ 25"
+            LiftAttempt {
+                rounding: Rounding::parse("1.25 nearest").unwrap(),
+                ..attempt(doobee_doos("Doobee doos -> 3x5 @ 0.222r"), Some(100.0))
+            }
+            .to_string(),
+            "Doobee doos -> 3x5 @ 22.5 lb"
         );
         assert_eq!(
-            LiftAttempt{
-                    lift: Lift::parse("Doobee doos -> 3x5 @ 0.222r").unwrap(),
-                    weight: Some(100)
-            }.to_string(),
-            "Doobee doos -> 3x5 @ 25"
+            LiftAttempt {
+                rounding: Rounding::parse("5 down").unwrap(),
+                ..attempt(doobee_doos("Doobee doos -> 3x5 @ add5"), Some(22.0))
+            }
+            .to_string(),
+            "Doobee doos -> 3x5 @ 20 lb"
         );
     }
 
     #[test]
     fn can_make_lift_attempt() {
+        let pullups = |notation: &str, weight| attempt(Lift::parse(notation).unwrap(), weight);
+        assert_eq!(pullups("Pullups -> 3x5", None).to_string(), "Pullups -> 3x5");
         assert_eq!(
-            format!(
-                "{}",
-                LiftAttempt {
-                    lift: Lift::parse("Pullups -> 3x5").unwrap(),
-                    weight: None
-                }
-            ),
-            "Pullups -> 3x5"
+            pullups("Pullups -> 3x5 @ 0.5r+10", Some(100.0)).to_string(),
+            "Pullups -> 3x5 @ 60 lb"
         );
+        assert_eq!(pullups("Pullups -> 3x5 @ any", None).to_string(), "Pullups -> 3x5 @ any");
+        assert_eq!(pullups("Pullups -> 3x5 @ add10", None).to_string(), "Pullups -> 3x5 @ any");
         assert_eq!(
-            format!(
-                "{}",
-                LiftAttempt {
-                    lift: Lift::parse("Pullups -> 3x5 @ 0.5r+10").unwrap(),
-                    weight: Some(100),
-                }
-            ),
-            "Pullups -> 3x5 @ 60"
+            pullups("Pullups -> 3x5 @ add10", Some(25.0)).to_string(),
+            "Pullups -> 3x5 @ 25 lb"
         );
+    }
+
+    #[test]
+    fn can_have_back_off_sets() {
+        let lift = Lift::parse("Squat -> 1x3+ @ 1r[squat], 3x8 @ 0.8r[squat], 1xAny @ any").unwrap();
+        assert_eq!(lift.sets, vec![Set::parse("3+").unwrap()]);
+        assert_eq!(lift.weight, WeightScheme::parse("1r[squat]").unwrap());
+        assert_eq!(lift.back_off.len(), 2);
+        assert_eq!(lift.back_off[0].weight, WeightScheme::parse("0.8r[squat]").unwrap());
+        assert_eq!(lift.all_sets().len(), 5);
         assert_eq!(
-            format!(
-                "{}",
-                LiftAttempt {
-                    lift: Lift::parse("Pullups -> 3x5 @ any").unwrap(),
-                    weight: None,
-                }
-            ),
-            "Pullups -> 3x5 @ any"
+            lift.to_string(),
+            "Squat -> 1x3+ @ 1r[squat], 3x8 @ 0.8r[squat], 1xAny @ any"
         );
         assert_eq!(
-            format!(
-                "{}",
-                LiftAttempt {
-                    lift: Lift::parse("Pullups -> 3x5 @ add10").unwrap(),
-                    weight: None,
-                }
-            ),
-            "Pullups -> 3x5 @ any"
+            LiftAttempt {
+                back_off_weights: vec![Some(300.0), None],
+                ..attempt(lift, Some(300.0))
+            }
+            .to_string(),
+            "Squat -> 1x3+ @ 300 lb, 3x8 @ 240 lb, 1xAny @ any"
+        );
+    }
+
+    #[test]
+    fn can_have_effort_targets() {
+        let lift = Lift::parse("Squat -> 3x5 @ 1r @ RPE8, 2x8 @ 2RIR @ 0.8r").unwrap();
+        assert_eq!(lift.effort(), Some(Effort::Rpe(8.0)));
+        assert_eq!(lift.sets[2].effort(), Some(Effort::Rpe(8.0)));
+        assert_eq!(lift.back_off[0].effort(), Some(Effort::Rir(2)));
+        assert_eq!(lift.to_string(), "Squat -> 3x5 @ RPE8 @ 1r, 2x8 @ 2RIR @ 0.8r");
+        assert_eq!(lift.own_progression(), None);
+        let autoregulated = Lift::parse("Squat -> 3x5 @ RPE8 @ 1r progress rpe").unwrap();
+        assert_eq!(autoregulated.own_progression(), Some(ProgressionScheme::EffortAutoregulation));
+        assert_eq!(
+            Lift::parse("Pullup -> 3x8 @ RPE8").unwrap().to_string(),
+            "Pullup -> 3x8 @ RPE8"
         );
+        assert!(Lift::parse("Squat -> 3x5 @ 1r @ 2r").is_err());
+        let squat = Lift::parse("Squat -> 3x5 @ RPE8 @ 1r progress linear").unwrap();
+        assert_eq!(attempt(squat, Some(200.0)).to_string(), "Squat -> 3x5 @ RPE8 @ 200 lb");
+    }
+
+    #[test]
+    fn completes_conditioning_sets() {
+        let lift = Lift::parse("Finisher -> 1x400m,3xEMOM(5),1xAMRAP(10min)").unwrap();
+        let performed = |reps: Vec<usize>| LiftAttemptResult::performed(reps, None, None);
+        assert!(performed(vec![1, 5, 5, 5, 6]).is_completed(&lift));
+        assert!(!performed(vec![1, 5, 4, 5, 6]).is_completed(&lift));
+        assert!(!performed(vec![0, 5, 5, 5, 6]).is_completed(&lift));
+        let timed = performed(vec![1, 5, 5, 5, 6])
+            .with_time_taken(vec![Some(Duration::from_secs(95)), None, None, None, None]);
+        assert_eq!(timed.to_string(), "Performed 1,5,5,5,6 in 1min35s,-,-,-,-");
+        assert_eq!(LiftAttemptResult::parse(&timed.to_string()).unwrap(), timed);
+    }
+
+    #[test]
+    fn can_do_sets_in_parts() {
+        let lift = Lift::parse("Curl -> 1x10 drop 2,1x8+rp3,1x(2+2+2) @ 1r[curl]").unwrap();
+        assert_eq!(lift.to_string(), "Curl -> 1x10 drop 2,1x8+rp3,1x(2+2+2) @ 1r[curl]");
+        let attempt = attempt(lift.clone(), Some(50.0));
+        assert_eq!(attempt.drop_weights(0), vec![40.0, 30.0]);
+        assert!(attempt.drop_weights(1).is_empty());
+        let result = LiftAttemptResult::parse("Performed 10+6+4,8+3+2+2,2+2+2 @ 50").unwrap();
         assert_eq!(
-            format!(
-                "{}",
-                LiftAttempt {
-                    lift: Lift::parse("Pullups -> 3x5 @ add10").unwrap(),
-                    weight: Some(25),
-                }
-            ),
-            "Pullups -> 3x5 @ 25"
+            result,
+            LiftAttemptResult::performed_in_parts(
+                vec![vec![10, 6, 4], vec![8, 3, 2, 2], vec![2, 2, 2]],
+                Some(50.0),
+                None
+            )
+        );
+        assert_eq!(result.to_string(), "Performed 10+6+4,8+3+2+2,2+2+2 @ 50");
+        assert!(result.is_completed(&lift));
+        assert!(!LiftAttemptResult::parse("Performed 10+6+4,7+3+2+2,2+2+1")
+            .unwrap()
+            .is_completed(&lift));
+        assert!(!LiftAttemptResult::parse("Performed 10+6+4,8+3+2+2,3+2+1")
+            .unwrap()
+            .is_completed(&lift));
+        let steeper = LiftAttempt {
+            lift: Lift::parse("Curl -> 1x10 drop 2 by 25% @ 1r[curl]").unwrap(),
+            ..attempt
+        };
+        assert_eq!(steeper.drop_weights(0), vec![35.0, 25.0]);
+    }
+
+    #[test]
+    fn can_be_done_per_side_and_per_hand() {
+        let lift = Lift::parse("Split squat -> 2x10/side,1x8-12/side @ add10/hand equipment dumbbell").unwrap();
+        assert!(lift.per_side && lift.per_hand);
+        assert_eq!(lift.sets.len(), 3);
+        assert_eq!(
+            lift.to_string(),
+            "Split squat -> 2x10/side,1x8-12/side @ add10/hand equipment dumbbell"
+        );
+        let attempt = attempt(lift, Some(30.0));
+        assert_eq!(attempt.to_string(), "Split squat -> 2x10,1x8-12 per side @ 30 lb per hand");
+        let performed = LiftAttemptResult::parse("Performed 10,10,9 @ 25").unwrap();
+        assert_eq!(attempt.volume(&performed), 29.0 * 25.0 * 4.0);
+        let each_side = LiftAttemptResult::parse("Performed 10/9,10/10,9/8 @ 25").unwrap();
+        assert_eq!(each_side.to_string(), "Performed 10/9,10/10,9/8 @ 25");
+        assert_eq!(
+            each_side,
+            LiftAttemptResult::performed_per_side(
+                vec![[vec![10], vec![9]], [vec![10], vec![10]], [vec![9], vec![8]]],
+                Some(25.0),
+                None
+            )
+        );
+        assert!(!each_side.is_completed(&attempt.lift));
+        assert_eq!(attempt.volume(&each_side), 56.0 * 25.0 * 2.0);
+        assert_eq!(attempt.volume(&LiftAttemptResult::NotCompleted), 0.0);
+        let squat = Lift::parse("Squat -> 1x5 @ 1r, 1x8 @ 0.5r, 1x30s @ any").unwrap();
+        assert!(!squat.per_side && !squat.per_hand);
+        let attempt = LiftAttempt {
+            lift: squat,
+            back_off_weights: vec![Some(200.0), None],
+            ..attempt
+        };
+        assert_eq!(
+            attempt.volume(&LiftAttemptResult::Completed { completed_maximum_reps: false }),
+            5.0 * 30.0 + 8.0 * 100.0
         );
+        let lighter_back_off = LiftAttemptResult::parse("Performed 5,8,30 @ 30, 90").unwrap();
+        assert_eq!(attempt.volume(&lighter_back_off), 5.0 * 30.0 + 8.0 * 90.0);
+    }
+
+    #[test]
+    fn can_have_rest_and_tempo() {
+        let lift = Lift::parse("Squat -> 4x3,1x5 @ 1.35r rest 3min tempo 3010").unwrap();
+        assert_eq!(lift.rest, Some(Rest::parse("3min").unwrap()));
+        assert_eq!(lift.to_string(), "Squat -> 4x3,1x5 @ 1.35r rest 3min tempo 3010");
+        assert_eq!(
+            lift.time_under_tension(),
+            [12, 12, 12, 12, 20].map(Duration::from_secs).to_vec()
+        );
+        assert!(Lift::parse("Plank -> 3x30s").unwrap().time_under_tension().is_empty());
+        assert!(Lift::parse("Squat -> 3x5 @ 1r rest soon").is_err());
     }
 
     #[test]
@@ -336,9 +1480,36 @@ mod tests {
                     Set::parse("5-7").unwrap(),
                     Set::parse("5+").unwrap(),
                 ],
-                weight: WeightScheme::parse("0.2r").unwrap()
+                weight: WeightScheme::parse("0.2r").unwrap(),
+                back_off: vec![],
+                progression: None,
+                rounding: None,
+                equipment: None,
+                warmup: None,
+                rest: None,
+                tempo: None,
+                graduation: None,
+                per_side: false,
+                per_hand: false,
             }
         );
+        assert_eq!(
+            Lift::parse("Squat -> 3x5 @ 1r[squat] progress gzclp-t1+10")
+                .unwrap()
+                .progression,
+            Some(ProgressionScheme::Gzclp {
+                tier: 1,
+                increment: Some(10.0)
+            })
+        );
+        assert!(Lift::parse("Squat -> 3x5 @ 1r progress sideways").is_err());
+        let cable_row = Lift::parse("Cable row -> 3x10 @ add5 progress double round 2.5 nearest").unwrap();
+        assert_eq!(cable_row.rounding, Some(Rounding::parse("2.5 nearest").unwrap()));
+        assert_eq!(
+            cable_row.to_string(),
+            "Cable row -> 3x10 @ add5 progress double round 2.5 nearest"
+        );
+        assert!(Lift::parse("Squat -> 3x5 @ 1r round").is_err());
     }
 
     #[test]
@@ -361,5 +1532,39 @@ mod tests {
             ),
             "Pullups -> 2x5,1x5-7,1x5+ @ 0.2r"
         );
+        assert_eq!(
+            format!("{}", Lift::parse("Pullups -> 3x7+ progress linear+1").unwrap()),
+            "Pullups -> 3x7+ progress linear+1"
+        );
+    }
+
+    #[test]
+    fn round_trips_through_notation() {
+        [
+            "Squat -> 4x3,1x3+ @ 1.35r[squat]-10, 3x8 @ RPE7 @ 0.8r[squat] rest 3min tempo 3010",
+            "Box jumps -> 3x5s",
+            "\"Box jump -> step down\" -> 3x5 @ bw",
+            "\"Press = push\" -> 3x10/side @ add5/hand round 2.5 nearest equipment dumbbell",
+            "\"Dumbbell \\\"heavy\\\" row\" -> 3x10 @ 0.5r[\"row, heavy\"]+5",
+            "Pullup -> 3x5+ progress reps graduate at 12 to \"Pullup @ the rings\" @ add5 from 10",
+            "Pushup -> 3x10+ graduate at 20 to \"Pushup with rest pause\" @ bw+10",
+            "Leg press -> 3x10 @ add20 warmup 50% x10, 75% x5",
+        ]
+        .iter()
+        .for_each(|notation| {
+            let lift = Lift::parse(notation).unwrap();
+            assert_eq!(lift.to_string(), *notation);
+            assert_eq!(Lift::parse(&lift.to_string()).unwrap(), lift);
+        });
+        assert_eq!(
+            Lift::parse("Pushup -> 3x10+ graduate at 20 to \"Pushup with rest pause\" @ bw+10")
+                .unwrap()
+                .graduation
+                .unwrap()
+                .name,
+            "Pushup with rest pause"
+        );
+        assert!(Lift::parse("Squat -> 3x5 @ 1r progres linear").is_err());
+        assert!(Lift::parse("Plank -> 1x30sec").is_err());
     }
 }
@@ -1,6 +1,16 @@
 pub mod lifting;
+pub mod notation;
+pub mod plates;
 pub mod programs;
 pub mod user_input;
 pub mod weight_scheme;
 pub mod set;
 pub mod day;
+pub mod legacy;
+pub mod templates;
+pub mod progression;
+pub mod rounding;
+pub mod settings;
+pub mod timing;
+pub mod units;
+pub mod warmups;
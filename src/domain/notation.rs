@@ -0,0 +1,478 @@
+use anyhow::{anyhow, Error, Result};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// A piece of notation that couldn't be parsed, with what was probably meant, if it was
+/// a near miss. Pieces made of smaller ones (like a lift, made of its sets and weight) keep
+/// the error of the smaller piece as their cause, so a `Diagnostic` can narrow down the exact
+/// text that's wrong.
+#[derive(Debug)]
+pub struct NotationError {
+    pub message: String,
+    /// The text that couldn't be parsed.
+    pub fragment: String,
+    pub suggestion: Option<String>,
+    pub cause: Option<Error>,
+    /// Where the fragment was in memory, so it can be found in the text it was taken from
+    /// even if the same text is there more than once.
+    address: usize,
+}
+
+impl Display for NotationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.message, self.fragment.trim())?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ". Did you mean '{suggestion}'?")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for NotationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause.as_ref().map(|cause| cause.as_ref() as _)
+    }
+}
+
+impl NotationError {
+    pub fn new(message: &str, fragment: &str) -> Self {
+        NotationError {
+            message: message.to_string(),
+            fragment: fragment.to_string(),
+            suggestion: None,
+            cause: None,
+            address: fragment.as_ptr() as usize,
+        }
+    }
+
+    pub fn suggesting(self, suggestion: Option<String>) -> Self {
+        NotationError { suggestion, ..self }
+    }
+
+    pub fn because(self, cause: Error) -> Self {
+        NotationError {
+            cause: Some(cause),
+            ..self
+        }
+    }
+
+    /// Where the fragment is within the text, if it was taken from there.
+    fn span_in(&self, text: &str) -> Option<Range<usize>> {
+        let start = self.address.checked_sub(text.as_ptr() as usize)?;
+        let end = start + self.fragment.len();
+        (text.get(start..end) == Some(self.fragment.as_str())).then_some(start..end)
+    }
+}
+
+pub trait NotationContext<T> {
+    /// Wraps an error in parsing the fragment, keeping it as the cause.
+    fn notation(self, message: &str, fragment: &str) -> Result<T>;
+}
+
+impl<T> NotationContext<T> for Result<T> {
+    fn notation(self, message: &str, fragment: &str) -> Result<T> {
+        self.map_err(|error| NotationError::new(message, fragment).because(error).into())
+    }
+}
+
+/// The candidate the word is most likely a typo of, if any is within a couple of typos.
+/// ```
+/// # use yawa::domain::notation::suggest;
+/// assert_eq!(suggest("progres", &["progress", "round"]), Some("progress"));
+/// assert_eq!(suggest("Round", &["progress", "round"]), Some("round"));
+/// assert_eq!(suggest("weight", &["progress", "round"]), None);
+/// ```
+pub fn suggest<'a>(word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(&word, &candidate.to_lowercase()), *candidate))
+        .filter(|(distance, candidate)| *distance <= 2.min(candidate.len() / 2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// How many characters have to be added, removed or changed to turn one word into the other.
+fn edit_distance(from: &str, to: &str) -> usize {
+    let to: Vec<char> = to.chars().collect();
+    let mut previous: Vec<usize> = (0..=to.len()).collect();
+    from.chars().enumerate().for_each(|(index, from)| {
+        let mut current = vec![index + 1];
+        to.iter().enumerate().for_each(|(other, to)| {
+            let change = previous[other] + usize::from(from != *to);
+            current.push(change.min(previous[other + 1] + 1).min(current[other] + 1));
+        });
+        previous = current;
+    });
+    previous[to.len()]
+}
+
+/// Where a problem is in a program's notation: its line and column (counting from 1),
+/// with the text there underlined.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    /// How many characters are underlined.
+    pub width: usize,
+    pub source_line: String,
+    pub suggestion: Option<String>,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "{gutter}--> line {}, column {}", self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width)
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\n{gutter} = did you mean '{suggestion}'?")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// Every problem found in a program's notation, in the order they're in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let diagnostics: Vec<String> = self.0.iter().map(|it| it.to_string()).collect();
+        write!(f, "{}", diagnostics.join("\n\n"))?;
+        if self.0.len() > 1 {
+            write!(f, "\n\nFound {} problems.", self.0.len())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+impl Diagnostic {
+    /// Points at the problem the error is about, within the span of the source that was being
+    /// parsed. Each `NotationError` in the error's chain narrows the span down to its fragment,
+    /// as long as the fragment can be found there, and the innermost one says what's wrong.
+    /// A fragment taken from the parsed text (the source as it was parsed, with the same
+    /// length) is found where it was taken from; any other is found by its first occurrence.
+    pub fn locate(source: &str, parsed: &str, span: Range<usize>, error: &Error) -> Self {
+        let mut span = span;
+        let mut message = error.to_string();
+        let mut suggestion = None;
+        let mut notation_found = false;
+        error.chain().for_each(|cause| match cause.downcast_ref::<NotationError>() {
+            Some(notation_error) => {
+                let fragment = notation_error.fragment.trim();
+                let leading =
+                    notation_error.fragment.len() - notation_error.fragment.trim_start().len();
+                if let Some(start) = notation_error
+                    .span_in(parsed)
+                    .filter(|it| span.start <= it.start && it.end <= span.end)
+                    .map(|it| it.start + leading - span.start)
+                    .or_else(|| source.get(span.clone()).and_then(|text| text.find(fragment)))
+                    .filter(|_| !fragment.is_empty())
+                {
+                    span = span.start + start..span.start + start + fragment.len();
+                }
+                message = format!("{}: {}", notation_error.message, fragment);
+                suggestion = notation_error.suggestion.clone().or(suggestion.clone());
+                notation_found = true;
+            }
+            None if notation_found => {
+                message = format!("{message} ({cause})");
+                notation_found = false;
+            }
+            None => {}
+        });
+        let text = source.get(span.clone()).unwrap_or_default();
+        let start = span.start + (text.len() - text.trim_start().len());
+        let end = (span.start + text.trim_end().len()).max(start);
+        let line_start = source[..start].rfind('\n').map_or(0, |it| it + 1);
+        let line_end = source[start..].find('\n').map_or(source.len(), |it| start + it);
+        Diagnostic {
+            message,
+            line: source[..start].matches('\n').count() + 1,
+            column: source[line_start..start].chars().count() + 1,
+            width: source[start..end.min(line_end)].chars().count().max(1),
+            source_line: source[line_start..line_end].trim_end().to_string(),
+            suggestion,
+        }
+    }
+}
+
+/// Where the slice is within the text it was taken from.
+pub fn span_of(text: &str, slice: &str) -> Range<usize> {
+    let start = (slice.as_ptr() as usize)
+        .checked_sub(text.as_ptr() as usize)
+        .filter(|start| start + slice.len() <= text.len())
+        .unwrap_or(0);
+    start..start + slice.len()
+}
+
+/// Writes the name the way `unquote()` reads it back: as it is, unless it has any of the
+/// separators around it in the notation (or a quote, a line break or tab, or spaces at either
+/// end), in which case it's put in quotes, with any quotes and backslashes in it escaped by
+/// a backslash.
+/// ```
+/// # use yawa::domain::notation::quote;
+/// assert_eq!(quote("Box jump", &[" | "]), "Box jump");
+/// assert_eq!(quote("Push | Pull", &[" | "]), "\"Push | Pull\"");
+/// assert_eq!(quote("6\" box jump", &[]), "\"6\\\" box jump\"");
+/// assert_eq!(quote("Push\tPull", &[]), "\"Push\tPull\"");
+/// ```
+pub fn quote(name: &str, separators: &[&str]) -> String {
+    let padded = format!(" {name} ");
+    if name.trim() != name
+        || name.starts_with('[')
+        || name.contains(['"', '\n', '\r', '\t'])
+        || separators.iter().any(|separator| padded.contains(separator))
+    {
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        name.to_string()
+    }
+}
+
+/// Reads a name written by `quote()`: text in quotes, where a backslash escapes the character
+/// after it, or trimmed text without any quotes.
+/// ```
+/// # use yawa::domain::notation::unquote;
+/// assert_eq!(unquote(" Box jump ").unwrap(), "Box jump");
+/// assert_eq!(unquote("\"Push | Pull\"").unwrap(), "Push | Pull");
+/// unquote("\"Push | Pull").is_err();
+/// unquote("6\" box jump").is_err();
+/// ```
+pub fn unquote(notation: &str) -> Result<String> {
+    let notation = notation.trim();
+    let Some(quoted) = notation.strip_prefix('"') else {
+        if notation.contains('"') {
+            return Err(NotationError::new("A name with a '\"' in it must be in quotes", notation)
+                .suggesting(Some(quote(notation, &[])))
+                .into());
+        }
+        return Ok(notation.to_string());
+    };
+    let mut name = String::new();
+    let mut characters = quoted.chars();
+    loop {
+        match characters.next() {
+            Some('"') => break,
+            Some('\\') if !characters.as_str().is_empty() => {
+                name.extend(characters.next());
+            }
+            Some(character) => name.push(character),
+            None => {
+                return Err(NotationError::new("A name in quotes needs a closing '\"'", notation)
+                    .suggesting(Some(format!("{notation}\"")))
+                    .into())
+            }
+        }
+    }
+    if !characters.as_str().trim().is_empty() {
+        return Err(NotationError::new("Nothing can follow a name in quotes", notation).into());
+    }
+    Ok(name)
+}
+
+/// Whether each byte of the text is outside of quotes. The quotes themselves are inside.
+fn outside_quotes(text: &str) -> Vec<bool> {
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut outside = vec![true; text.len()];
+    text.char_indices().for_each(|(index, character)| {
+        outside[index..index + character.len_utf8()].fill(!quoted && character != '"');
+        match character {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ => {}
+        }
+    });
+    outside
+}
+
+/// Turns line breaks and tabs into spaces, except within quotes, so notation written over
+/// several lines reads as one. The text keeps its length, so spans in it are spans in the
+/// original.
+/// ```
+/// # use yawa::domain::notation::flatten_unquoted;
+/// assert_eq!(flatten_unquoted("A |\n\"B\tC\"\t| D"), "A | \"B\tC\" | D");
+/// ```
+pub fn flatten_unquoted(text: &str) -> String {
+    let outside = outside_quotes(text);
+    text.char_indices()
+        .map(|(index, character)| match character {
+            '\n' | '\r' | '\t' if outside[index] => ' ',
+            _ => character,
+        })
+        .collect()
+}
+
+/// Like `str::split()`, except for separators within quotes.
+/// ```
+/// # use yawa::domain::notation::split_unquoted;
+/// assert_eq!(split_unquoted("A | \"B | C\" | D", " | "), vec!["A", "\"B | C\"", "D"]);
+/// ```
+pub fn split_unquoted<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let outside = outside_quotes(text);
+    let mut parts = Vec::new();
+    let mut start = 0;
+    text.match_indices(separator)
+        .filter(|(index, _)| outside[*index])
+        .for_each(|(index, _)| {
+            parts.push(&text[start..index]);
+            start = index + separator.len();
+        });
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Like `str::split_once()`, except for separators within quotes.
+pub fn split_once_unquoted<'a>(text: &'a str, separator: &str) -> Option<(&'a str, &'a str)> {
+    let outside = outside_quotes(text);
+    text.match_indices(separator)
+        .find(|(index, _)| outside[*index])
+        .map(|(index, _)| (&text[..index], &text[index + separator.len()..]))
+}
+
+/// Like `str::split_whitespace()`, keeping text in quotes within one word.
+pub fn words(text: &str) -> Vec<&str> {
+    let outside = outside_quotes(text);
+    let mut words = Vec::new();
+    let mut start = None;
+    text.char_indices().for_each(|(index, character)| {
+        if character.is_whitespace() && outside[index] {
+            if let Some(start) = start.take() {
+                words.push(&text[start..index]);
+            }
+        } else if start.is_none() {
+            start = Some(index);
+        }
+    });
+    words.extend(start.map(|start| &text[start..]));
+    words
+}
+
+/// A whole number, written with digits only.
+/// ```
+/// # use yawa::domain::notation::count;
+/// assert_eq!(count("12").unwrap(), 12);
+/// count("+12").is_err();
+/// count("12 ").is_err();
+/// ```
+pub fn count(notation: &str) -> Result<usize> {
+    if notation.is_empty() || !notation.chars().all(|it| it.is_ascii_digit()) {
+        return Err(anyhow!("Not a whole number: {notation}"));
+    }
+    Ok(notation.parse()?)
+}
+
+/// A number, written with digits, maybe a decimal point, and maybe a minus sign in front.
+/// ```
+/// # use yawa::domain::notation::decimal;
+/// assert_eq!(decimal("-2.5").unwrap(), -2.5);
+/// decimal("+2.5").is_err();
+/// decimal("inf").is_err();
+/// decimal("1e3").is_err();
+/// ```
+pub fn decimal(notation: &str) -> Result<f64> {
+    let digits = notation.strip_prefix('-').unwrap_or(notation);
+    if !digits.chars().any(|it| it.is_ascii_digit())
+        || !digits.chars().all(|it| it.is_ascii_digit() || it == '.')
+        || digits.matches('.').count() > 1
+    {
+        return Err(anyhow!("Not a number: {notation}"));
+    }
+    Ok(notation.parse()?)
+}
+
+/// A number with a sign in front, like '+10' or '-2.5', or nothing, for zero.
+pub fn offset(notation: &str) -> Result<f64> {
+    match notation.strip_prefix('+') {
+        _ if notation.is_empty() => Ok(0.0),
+        Some(offset) if !offset.starts_with('-') => decimal(offset),
+        None if notation.starts_with('-') => decimal(notation),
+        _ => Err(anyhow!("Not an offset, like '+10' or '-10': {notation}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn points_at_the_innermost_fragment() {
+        let source = "My program !!! 135 !!!\nLegs | Squat -> 3x5 @ 1r | Calf raise -> 3x15 @ ad20";
+        let day = &source[23..];
+        let error: Error = NotationError::new("Cannot parse lift", "Calf raise -> 3x15 @ ad20")
+            .because(
+                NotationError::new("Cannot parse weight", "ad20")
+                    .suggesting(Some("add20".to_string()))
+                    .into(),
+            )
+            .into();
+        let diagnostic = Diagnostic::locate(source, source, span_of(source, day), &error);
+        assert_eq!(
+            diagnostic,
+            Diagnostic {
+                message: "Cannot parse weight: ad20".to_string(),
+                line: 2,
+                column: 49,
+                width: 4,
+                source_line: "Legs | Squat -> 3x5 @ 1r | Calf raise -> 3x15 @ ad20".to_string(),
+                suggestion: Some("add20".to_string()),
+            }
+        );
+        assert_eq!(
+            diagnostic.to_string(),
+            "Cannot parse weight: ad20
+ --> line 2, column 49
+  |
+2 | Legs | Squat -> 3x5 @ 1r | Calf raise -> 3x15 @ ad20
+  |                                                 ^^^^
+  = did you mean 'add20'?"
+        );
+    }
+
+    #[test]
+    fn points_at_where_a_repeated_fragment_was_taken_from() {
+        let source = "Legs | Squat -> 3x5 @ 1r | Squat -> 3x8 @ 0.8r";
+        let second = &source[27..32];
+        let error: Error =
+            NotationError::new("A day can't have two lifts with the same name", second).into();
+        let diagnostic = Diagnostic::locate(source, source, 0..source.len(), &error);
+        assert_eq!((diagnostic.line, diagnostic.column, diagnostic.width), (1, 28, 5));
+        let copied: Error = NotationError::new("Cannot parse lift", "Squat").into();
+        let diagnostic = Diagnostic::locate(source, source, 0..source.len(), &copied);
+        assert_eq!((diagnostic.line, diagnostic.column, diagnostic.width), (1, 8, 5));
+    }
+
+    #[test]
+    fn falls_back_to_the_whole_span() {
+        let source = "A | Squat -> 3x5\nB | Bench -> 3x5";
+        let error = Err::<(), Error>(anyhow!("invalid digit found in string"))
+            .notation("Cannot parse set", "five")
+            .unwrap_err();
+        let diagnostic = Diagnostic::locate(source, source, 17..source.len(), &error);
+        assert_eq!(diagnostic.message, "Cannot parse set: five (invalid digit found in string)");
+        assert_eq!((diagnostic.line, diagnostic.column, diagnostic.width), (2, 1, 16));
+    }
+
+    #[test]
+    fn suggests_close_words() {
+        assert_eq!(suggest("equipmnet", &["equipment", "warmup"]), Some("equipment"));
+        assert_eq!(suggest("xyz", &["rpe", "reps"]), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::domain::notation::{suggest, NotationError};
+use crate::domain::units::Unit;
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+
+/// Plate weights are worked out in hundredths, so 1.25 plates add up exactly.
+const PRECISION: f64 = 100.0;
+
+/// How many plates fit on each side of a bar, at most.
+const PLATES_PER_SIDE: usize = 20;
+
+/// What a lift is done with. Only barbell lifts get loaded with plates.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Equipment {
+    #[default]
+    Barbell,
+    Dumbbell,
+    Machine,
+    /// Weight added to the body, like a weighted pullup with a dip belt.
+    Bodyweight,
+}
+
+impl Display for Equipment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Equipment::Barbell => write!(f, "barbell"),
+            Equipment::Dumbbell => write!(f, "dumbbell"),
+            Equipment::Machine => write!(f, "machine"),
+            Equipment::Bodyweight => write!(f, "bodyweight"),
+        }
+    }
+}
+
+impl Equipment {
+    /// ```
+    /// # use yawa::domain::plates::Equipment;
+    /// Equipment::parse("barbell").unwrap();
+    /// Equipment::parse("dumbbell").unwrap();
+    /// Equipment::parse("machine").unwrap();
+    /// Equipment::parse("bodyweight").unwrap();
+    /// Equipment::parse("kettlebell").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        match notation.trim() {
+            "barbell" => Ok(Equipment::Barbell),
+            "dumbbell" => Ok(Equipment::Dumbbell),
+            "machine" => Ok(Equipment::Machine),
+            "bodyweight" => Ok(Equipment::Bodyweight),
+            other => Err(NotationError::new(
+                "Unknown equipment, use 'barbell', 'dumbbell', 'machine' or 'bodyweight'",
+                other,
+            )
+            .suggesting(
+                suggest(other, &["barbell", "dumbbell", "machine", "bodyweight"])
+                    .map(String::from),
+            )
+            .into()),
+        }
+    }
+}
+
+/// One size of plate, and how many of them can go on each side of the bar (as many as needed,
+/// if not given).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Plates {
+    pub weight: f64,
+    pub per_side: Option<usize>,
+}
+
+impl Display for Plates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.per_side {
+            Some(per_side) => write!(f, "{}x{}", self.weight, per_side),
+            None => write!(f, "{}", self.weight),
+        }
+    }
+}
+
+impl Plates {
+    /// Notation is a list of plate weights, each optionally followed by how many fit per side:
+    /// ```
+    /// # use yawa::domain::plates::Plates;
+    /// Plates::parse_all("45 25 10 5 2.5").unwrap();
+    /// Plates::parse_all("20x4 10x2 5 2.5 1.25").unwrap();
+    /// Plates::parse_all("").is_err();
+    /// Plates::parse_all("0").is_err();
+    /// Plates::parse_all("inf").is_err();
+    /// Plates::parse_all("45xmany").is_err();
+    /// ```
+    pub fn parse_all(notation: &str) -> Result<Vec<Self>> {
+        let plates = notation
+            .split_whitespace()
+            .map(|plate| -> Result<Plates> {
+                let (weight, per_side) = match plate.split_once('x') {
+                    Some((weight, per_side)) => (weight, Some(per_side.parse()?)),
+                    None => (plate, None),
+                };
+                let weight: f64 = weight.parse()?;
+                if !weight.is_finite() || weight < 1.0 / PRECISION {
+                    return Err(anyhow!("Plates must weigh more than nothing: {plate}"));
+                }
+                Ok(Plates { weight, per_side })
+            })
+            .collect::<Result<Vec<Plates>>>()?;
+        if plates.is_empty() {
+            return Err(anyhow!("At least one plate is needed."));
+        }
+        Ok(plates)
+    }
+
+    pub fn display_all(plates: &[Plates]) -> String {
+        plates
+            .iter()
+            .map(|plates| plates.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+/// The bar and plates available to load a barbell with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlateInventory {
+    pub bar: f64,
+    pub plates: Vec<Plates>,
+    pub unit: Unit,
+}
+
+impl PlateInventory {
+    /// A standard bar, with as many of the usual plates as needed.
+    pub fn standard(unit: Unit) -> Self {
+        let (bar, plates) = match unit {
+            Unit::Lb => (45.0, vec![45.0, 35.0, 25.0, 10.0, 5.0, 2.5]),
+            Unit::Kg => (20.0, vec![25.0, 20.0, 15.0, 10.0, 5.0, 2.5, 1.25]),
+        };
+        PlateInventory {
+            bar,
+            plates: plates
+                .into_iter()
+                .map(|weight| Plates {
+                    weight,
+                    per_side: None,
+                })
+                .collect(),
+            unit,
+        }
+    }
+
+    /// The heaviest weight worth working out how to load: the heaviest plate, as many times on
+    /// each side as fit on a bar.
+    pub fn most(&self) -> f64 {
+        let heaviest = self.plates.iter().map(|it| it.weight).fold(0.0, f64::max);
+        self.bar + 2.0 * heaviest * PLATES_PER_SIDE as f64
+    }
+
+    /// How to load the bar for the weight, using as few plates as possible. If it can't be
+    /// done exactly, loads the nearest weight that can (the lighter one, if two are as near).
+    pub fn load(&self, weight: f64) -> Result<PlateLoading> {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(anyhow!("Cannot load {weight} {}, it has to be a weight.", self.unit));
+        }
+        if weight > self.most() {
+            return Err(anyhow!(
+                "Cannot load {weight} {}, the most a bar takes is {} {}.",
+                self.unit,
+                self.most(),
+                self.unit
+            ));
+        }
+        let target = to_hundredths(((weight - self.bar) / 2.0).max(0.0));
+        let mut plates = self.plates.clone();
+        plates.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        let heaviest = plates.first().map(|it| to_hundredths(it.weight)).unwrap_or(0);
+        let limit = target + heaviest;
+        // For every weight per side up to the limit: the fewest plates that make it, if any can.
+        let mut best: Vec<Option<Vec<f64>>> = vec![None; limit + 1];
+        best[0] = Some(Vec::new());
+        let mut add = |plate: f64, total: usize, size: usize| {
+            if let Some(loaded) = &best[total - size] {
+                let mut loaded = loaded.clone();
+                loaded.push(plate);
+                // Fewer plates is better, then heavier ones, like 45 + 25 over 35 + 35.
+                let better = best[total].as_ref().is_none_or(|it| {
+                    it.len() > loaded.len() || (it.len() == loaded.len() && *it < loaded)
+                });
+                if better {
+                    best[total] = Some(loaded);
+                }
+            }
+        };
+        plates
+            .iter()
+            .filter(|plate| to_hundredths(plate.weight) > 0)
+            .for_each(|plate| {
+                let size = to_hundredths(plate.weight);
+                match plate.per_side {
+                    // Going up, a total can build on one that already has this plate.
+                    None => (size..=limit).for_each(|total| add(plate.weight, total, size)),
+                    // Going down, each pass adds at most one more of them.
+                    Some(count) => (0..count).for_each(|_| {
+                        (size..=limit).rev().for_each(|total| add(plate.weight, total, size))
+                    }),
+                }
+            });
+        let (_, per_side) = best
+            .into_iter()
+            .enumerate()
+            .filter_map(|(total, loaded)| loaded.map(|loaded| (total, loaded)))
+            .min_by_key(|(total, _)| (total.abs_diff(target), *total))
+            .unwrap_or((0, Vec::new()));
+        Ok(PlateLoading {
+            requested: weight,
+            weight: self.bar + 2.0 * per_side.iter().sum::<f64>(),
+            bar: self.bar,
+            per_side,
+            unit: self.unit,
+        })
+    }
+}
+
+fn to_hundredths(weight: f64) -> usize {
+    (weight * PRECISION).round() as usize
+}
+
+/// Which plates go on each side of the bar to reach a weight.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlateLoading {
+    pub requested: f64,
+    /// What the bar weighs once loaded, which is the requested weight if it could be made.
+    pub weight: f64,
+    pub bar: f64,
+    /// Heaviest first.
+    pub per_side: Vec<f64>,
+    pub unit: Unit,
+}
+
+impl PlateLoading {
+    pub fn is_exact(&self) -> bool {
+        (self.weight - self.requested).abs() < 1e-6
+    }
+}
+
+impl Display for PlateLoading {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.is_exact() {
+            write!(
+                f,
+                "can't load {} {} exactly, nearest is {} {}: ",
+                self.requested, self.unit, self.weight, self.unit
+            )?;
+        }
+        if self.per_side.is_empty() {
+            write!(f, "just the {} {} bar", self.bar, self.unit)
+        } else {
+            write!(
+                f,
+                "{} {} bar + {} per side",
+                self.bar,
+                self.unit,
+                self.per_side
+                    .iter()
+                    .map(|plate| plate.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_with_the_fewest_plates() {
+        let inventory = PlateInventory::standard(Unit::Lb);
+        let loading = inventory.load(275.0).unwrap();
+        assert_eq!(loading.per_side, vec![45.0, 45.0, 25.0]);
+        assert!(loading.is_exact());
+        assert_eq!(loading.to_string(), "45 lb bar + 45, 45, 25 per side");
+        assert_eq!(inventory.load(115.0).unwrap().per_side, vec![35.0]);
+        assert_eq!(inventory.load(45.0).unwrap().to_string(), "just the 45 lb bar");
+        let kg = PlateInventory::standard(Unit::Kg).load(62.5).unwrap();
+        assert_eq!(kg.per_side, vec![20.0, 1.25]);
+    }
+
+    #[test]
+    fn suggests_the_nearest_loadable_weight() {
+        let inventory = PlateInventory {
+            bar: 45.0,
+            plates: Plates::parse_all("45x2 10x1 5").unwrap(),
+            unit: Unit::Lb,
+        };
+        let loading = inventory.load(277.5).unwrap();
+        assert!(!loading.is_exact());
+        assert_eq!(loading.weight, 275.0);
+        assert_eq!(
+            loading.to_string(),
+            "can't load 277.5 lb exactly, nearest is 275 lb: 45 lb bar + 45, 45, 10, 5, 5, 5 per side"
+        );
+        assert_eq!(inventory.load(30.0).unwrap().to_string(), "can't load 30 lb exactly, nearest is 45 lb: just the 45 lb bar");
+    }
+
+    #[test]
+    fn refuses_what_isnt_a_weight_or_is_too_heavy() {
+        let inventory = PlateInventory::standard(Unit::Lb);
+        [f64::INFINITY, f64::NAN, -10.0, 1e9].iter().for_each(|weight| {
+            assert!(inventory.load(*weight).is_err(), "{weight}");
+        });
+        assert_eq!(inventory.most(), 1845.0);
+        assert_eq!(inventory.load(1845.0).unwrap().per_side, vec![45.0; 20]);
+        assert!(inventory.load(1850.0).is_err());
+    }
+
+    #[test]
+    fn can_parse_and_display_plates() {
+        let plates = Plates::parse_all("45x4 25 2.5").unwrap();
+        assert_eq!(plates[0].per_side, Some(4));
+        assert_eq!(Plates::display_all(&plates), "45x4 25 2.5");
+    }
+}
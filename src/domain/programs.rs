@@ -1,104 +1,1024 @@
 use crate::domain::lifting::*;
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
 use crate::domain::day::Day;
+use crate::domain::notation::{
+    decimal, flatten_unquoted, quote, span_of, split_once_unquoted, split_unquoted, suggest,
+    unquote, Diagnostic, Diagnostics, NotationContext, NotationError,
+};
+use crate::domain::plates::{Equipment, PlateInventory, PlateLoading};
+use crate::domain::progression::{
+    DeloadRule, Graduation, Prescription, ProgressionScheme, ProgressionState,
+};
+use crate::domain::rounding::{Rounding, RoundingDirection};
+use crate::domain::set::Set;
+use crate::domain::settings::Settings;
+use crate::domain::templates;
+use crate::domain::units::Unit;
+use crate::domain::warmups::WarmupSet;
 use crate::domain::weight_scheme::*;
 
+/// A weight that was dropped because it stopped going up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deload {
+    /// The name of the lift, or of the reference weight.
+    pub what: String,
+    pub from: f64,
+    pub to: f64,
+    pub unit: Unit,
+    pub stalls: usize,
+}
+
+impl Display for Deload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Deload: {} stalled {} times in a row, dropping it from {} {} to {} {}",
+            self.what, self.stalls, self.from, self.unit, self.to, self.unit
+        )
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Program {
     pub name: String,
     pub workouts_completed: usize,
-    pub reference_weight: usize,
-    pub starting_reference_weight: usize,
+    pub reference_weight: f64,
+    pub starting_reference_weight: f64,
+    /// Reference weights other than the main one, by name. Each progresses on its own.
+    pub reference_weights: BTreeMap<String, f64>,
+    pub starting_reference_weights: BTreeMap<String, f64>,
     pub days: Vec<Day>,
-    pub weights: HashMap<Lift, usize>,
+    /// The weight of each lift that is based on its previous weight.
+    pub weights: HashMap<LiftId, f64>,
     pub current_day: usize,
     pub current_cycle_attempt_results: Vec<Vec<LiftAttemptResult>>,
+    pub settings: Settings,
+    /// Where each lift that is based on its previous weight is in its progression.
+    pub lift_progressions: HashMap<LiftId, ProgressionState>,
+    /// Where each reference weight is in its progression. `None` is the main reference weight.
+    pub reference_progressions: BTreeMap<Option<String>, ProgressionState>,
+    /// The lifter's bodyweight, as last logged.
+    pub bodyweight: Option<f64>,
+    /// The name and version of the template the program was started from, if it was.
+    pub template: Option<(String, usize)>,
 }
 
 #[derive(Debug, PartialEq)]
 pub struct NewProgram {
     pub name: String,
-    pub starting_reference_weight: usize,
+    pub starting_reference_weight: f64,
+    pub starting_reference_weights: BTreeMap<String, StartingReference>,
     pub days: Vec<Day>,
-    pub weights: HashMap<Lift, usize>,
+    pub weights: HashMap<LiftId, f64>,
+    pub settings: Settings,
+}
+
+/// Where a named reference weight starts, when a program is started.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StartingReference {
+    Fixed(f64),
+    RelativeToReference { multiplier: f64, offset: f64 },
+}
+
+impl Display for StartingReference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartingReference::Fixed(weight) => write!(f, "{weight}"),
+            StartingReference::RelativeToReference { multiplier, offset } => write!(
+                f,
+                "{}",
+                WeightScheme::BasedOnReference {
+                    multiplier: *multiplier,
+                    offset: *offset,
+                    reference: None
+                }
+            ),
+        }
+    }
+}
+
+impl StartingReference {
+    /// notation options:
+    /// ```
+    /// # use yawa::domain::programs::StartingReference;
+    /// StartingReference::parse("185").unwrap();
+    /// StartingReference::parse("1.35r").unwrap();
+    /// StartingReference::parse("1r[squat]").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        if let Ok(weight) = decimal(notation) {
+            return Ok(StartingReference::Fixed(weight));
+        }
+        match WeightScheme::parse(notation)? {
+            WeightScheme::BasedOnReference {
+                multiplier,
+                offset,
+                reference: None,
+            } => Ok(StartingReference::RelativeToReference { multiplier, offset }),
+            _ => Err(anyhow!("Invalid starting reference weight: {notation}")),
+        }
+    }
+
+    pub fn weight(&self, reference_weight: f64) -> f64 {
+        match self {
+            StartingReference::Fixed(weight) => *weight,
+            StartingReference::RelativeToReference { .. } => {
+                self.weight_added(reference_weight).max(0.0)
+            }
+        }
+    }
+
+    /// The weight for a reference only bodyweight lifts are based on, which is what's added
+    /// to the lifter's bodyweight, so it can be below zero for lifts that start out assisted.
+    pub fn weight_added(&self, reference_weight: f64) -> f64 {
+        match self {
+            StartingReference::Fixed(weight) => *weight,
+            StartingReference::RelativeToReference { multiplier, offset } => {
+                ((multiplier * reference_weight) + offset).ceil()
+            }
+        }
+    }
+}
+
+impl Display for NewProgram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.write_notation(f, [" !!! ", " !! ", " !!! "])
+    }
 }
 
 impl NewProgram {
+    /// Notation is made of sections separated by ` !!! `:
+    /// the name, the starting reference weight (optionally followed by named reference weights,
+    /// like `135, squat = 1.35r, bench = 135`, see `StartingReference::parse()`),
+    /// the days (separated by ` !! `, see `Day::parse()`),
+    /// optionally the starting weights of lifts that are based on their previous weight
+    /// (separated by ` !! `, each looking like `Lift name = 30`),
+    /// and optionally the program's settings (see `Settings::parse()`).
+    ///
+    /// Names with any of these separators in them are put in quotes (see `notation::quote()`),
+    /// and are written that way when the program is displayed, so it parses back the same.
+    ///
+    /// Line breaks are treated like spaces, so a program can be spread over several lines.
+    /// Every problem is reported at once, as `Diagnostics`, each pointing at the line and column
+    /// it's at. That includes days that parse but can't be done (see `problems_in()`).
+    /// ```
+    /// # use yawa::domain::programs::NewProgram;
+    /// NewProgram::parse("My program !!! 135 !!! Day One | Squat -> 3x5 @ 1r | Leg press -> 3x10 @ add20 !!! Leg press = 90").unwrap();
+    /// NewProgram::parse("My program !!! 135, squat = 1.35r !!! Day One | Squat -> 3x5 @ 1r[squat]").unwrap();
+    /// NewProgram::parse("My program !!! 135 !!! Day One | Squat -> 3x5 @ 1r !!! Bench press = 90").is_err();
+    /// NewProgram::parse("My program !!! 135 !!! Day One | Squat -> 3x5 @ 1r !!!  !!! progression = linear").unwrap();
+    /// NewProgram::parse("My program !!! 135 !!! Day One | Leg press -> 3x10 @ add20").is_err();
+    /// NewProgram::parse("My program !!! 135 !!! Day One | Squat -> 3x5 @ 1r !! Day Two |").is_err();
+    /// ```
     pub fn parse(notation: &str) -> Result<Self> {
+        Self::parse_with_warnings(notation).map(|(program, _)| program)
+    }
+
+    /// Like `parse()`, also failing on what's probably a mistake, even though the program can
+    /// be done (see `warnings_in()`, `unused_starting_weights()` and `unused_references()`).
+    /// ```
+    /// # use yawa::domain::programs::NewProgram;
+    /// NewProgram::lint("My program !!! 135 !!! Day One | Squat -> 3x5 @ 1r").unwrap();
+    /// NewProgram::lint("My program !!! 135 !!! Day One | Squat -> 3x15-10 @ 1r").is_err();
+    /// NewProgram::lint("My program !!! 135 !!! Day One | Squat -> 3x5 @ 1r !!! Squat = 90").is_err();
+    /// ```
+    pub fn lint(notation: &str) -> Result<()> {
+        let (_, warnings) = Self::parse_with_warnings(notation)?;
+        match warnings.is_empty() {
+            true => Ok(()),
+            false => Err(Diagnostics(warnings).into()),
+        }
+    }
+
+    fn parse_with_warnings(notation: &str) -> Result<(Self, Vec<Diagnostic>)> {
         let major_delimiter = " !!! ";
         let minor_delimiter = " !! ";
-        let error = "Could not parse NewProgram";
-        let mut major_split = notation.split(major_delimiter);
-        Ok(NewProgram {
-            name: major_split.next().ok_or(anyhow!(error))?.to_string(),
-            starting_reference_weight: major_split.next().ok_or(anyhow!(error))?.parse()?,
-            days: major_split
-                .next()
-                .map(|days| {
-                    days.split(minor_delimiter)
-                        .flat_map(|day| Day::parse(day))
-                        .collect()
+        // Line breaks become spaces of the same length, so everything parsed from the text
+        // is at the same place as in the notation.
+        let text = flatten_unquoted(notation);
+        let mut problems = Vec::new();
+        let diagnose = |slice: &str, error: anyhow::Error| {
+            Diagnostic::locate(notation, &text, span_of(&text, slice), &error)
+        };
+        // Problems with a lift are looked for in its own notation, not the whole day's,
+        // which may have the same text in another lift.
+        let lift_span = |day: &str, index: Option<usize>| -> Range<usize> {
+            index
+                .and_then(|index| Day::lift_notations(day).get(index).copied())
+                .map_or(span_of(&text, day), |lift| span_of(&text, lift))
+        };
+        let mut major_split = split_unquoted(&text, major_delimiter).into_iter();
+        let name = major_split.next().unwrap_or_default();
+        let name = unquote(name).unwrap_or_else(|error| {
+            problems.push(diagnose(name, error));
+            Default::default()
+        });
+        let (Some(references), Some(days)) = (major_split.next(), major_split.next()) else {
+            let error = NotationError::new(
+                "A program needs a reference weight and days, each after a ' !!! '",
+                text.trim_end(),
+            );
+            problems.push(diagnose(text.trim_end(), error.into()));
+            return Err(Diagnostics(problems).into());
+        };
+        let ((starting_reference_weight, starting_reference_weights), references_parsed) =
+            match Self::parse_references(references) {
+                Ok(parsed) => (parsed, true),
+                Err(error) => {
+                    problems.push(diagnose(references, error));
+                    (Default::default(), false)
+                }
+            };
+        let days: Vec<(Day, &str)> = split_unquoted(days, minor_delimiter)
+            .into_iter()
+            .flat_map(|day| match Day::parse(day) {
+                Ok(parsed) => Some((parsed, day)),
+                Err(error) => {
+                    problems.push(diagnose(day, error));
+                    None
+                }
+            })
+            .collect();
+        let all_days_parsed = problems.is_empty();
+        let parsed_days: Vec<Day> = days.iter().map(|(day, _)| day.clone()).collect();
+        let weights_section = major_split.next();
+        let weights = match weights_section {
+            Some(weights) => Self::parse_weights(weights, minor_delimiter, &parsed_days)
+                .unwrap_or_else(|error| {
+                    // Lifts in days that didn't parse can't be found to give weights to.
+                    if all_days_parsed {
+                        problems.push(diagnose(weights, error));
+                    }
+                    Default::default()
+                }),
+            None => Default::default(),
+        };
+        let settings = match major_split.next() {
+            Some(settings) => Settings::parse(settings).unwrap_or_else(|error| {
+                problems.push(diagnose(settings, error));
+                Default::default()
+            }),
+            None => Default::default(),
+        };
+        let program = NewProgram {
+            name,
+            starting_reference_weight,
+            starting_reference_weights,
+            days: parsed_days,
+            weights,
+            settings,
+        };
+        // Weights based on references that didn't parse would be based on nothing.
+        let problems_in = |day: &Day| {
+            let mut problems = program.problems_in(day);
+            if references_parsed {
+                problems.extend(program.weights_below_zero_in(day));
+            }
+            problems
+        };
+        days.iter().for_each(|(day, slice)| {
+            problems_in(day).into_iter().for_each(|(index, problem)| {
+                problems.push(Diagnostic::locate(
+                    notation,
+                    &text,
+                    lift_span(slice, index),
+                    &problem.into(),
+                ))
+            })
+        });
+        if !problems.is_empty() {
+            return Err(Diagnostics(problems).into());
+        }
+        let mut warnings = Vec::new();
+        days.iter().for_each(|(day, slice)| {
+            program.warnings_in(day).into_iter().for_each(|(index, warning)| {
+                warnings.push(Diagnostic::locate(
+                    notation,
+                    &text,
+                    lift_span(slice, index),
+                    &warning.into(),
+                ))
+            })
+        });
+        program.unused_references().into_iter().for_each(|warning| {
+            warnings.push(diagnose(references, warning.into()))
+        });
+        program.unused_starting_weights().into_iter().for_each(|warning| {
+            warnings.push(diagnose(weights_section.unwrap_or_default(), warning.into()))
+        });
+        Ok((program, warnings))
+    }
+
+    /// The program's notation, the way `yawa fmt` writes it to a file: one day to a line,
+    /// with the weights and settings each on a line of their own.
+    /// ```
+    /// # use yawa::domain::programs::NewProgram;
+    /// let program = NewProgram::parse("Legs !!! 135 !!! A | Squat -> 3x5,3x5 @ 1r !! B | Leg press -> 3x10 @ add20 !!! Leg press = 90").unwrap();
+    /// assert_eq!(
+    ///     program.formatted(),
+    ///     "Legs !!! 135 !!!
+    ///     A | Squat -> 6x5 @ 1r !!
+    ///     B | Leg press -> 3x10 @ add20
+    /// !!! Leg press = 90
+    /// "
+    /// );
+    /// ```
+    pub fn formatted(&self) -> String {
+        let mut notation = String::new();
+        // Writing to a string can't fail.
+        let _ = self.write_notation(&mut notation, [" !!!\n    ", " !!\n    ", "\n!!! "]);
+        notation + "\n"
+    }
+
+    /// Writes the notation `parse()` reads, with what goes before the days, between days,
+    /// and before each section after the days.
+    fn write_notation(
+        &self,
+        f: &mut impl std::fmt::Write,
+        separators: [&str; 3],
+    ) -> std::fmt::Result {
+        let [before_days, between_days, before_section] = separators;
+        write!(
+            f,
+            "{} !!! {}",
+            quote(&self.name, &[" !!! "]),
+            self.starting_reference_weight
+        )?;
+        for (name, reference) in &self.starting_reference_weights {
+            write!(f, ", {} = {reference}", quote(name, &REFERENCE_SEPARATORS))?;
+        }
+        let days: Vec<String> = self.days.iter().map(|day| day.to_string()).collect();
+        write!(f, "{before_days}{}", days.join(between_days))?;
+        let mut named = BTreeSet::new();
+        let weights: Vec<String> = self
+            .days
+            .iter()
+            .flat_map(|day| day.lifts.iter())
+            .filter(|lift| named.insert(lift.id()))
+            .flat_map(|lift| {
+                let weight = self.weights.get(&lift.id())?;
+                Some(format!("{} = {weight}", quote(&lift.name, &NAME_SEPARATORS)))
+            })
+            .collect();
+        if !weights.is_empty() {
+            write!(f, "{before_section}{}", weights.join(" !! "))?;
+        }
+        if self.settings != Settings::default() {
+            // Without weights, their section is left empty, on the same line as the settings.
+            if weights.is_empty() {
+                write!(f, "{before_section} !!! ")?;
+            } else {
+                write!(f, "{before_section}")?;
+            }
+            write!(f, "{}", self.settings)?;
+        }
+        Ok(())
+    }
+
+    /// What's wrong with the day of this program, even though it parsed: it has no lifts,
+    /// two of its lifts have the same name (ignoring case and spacing), a lift that adds to
+    /// its previous weight has no weight to start from. Each problem comes with the index of
+    /// the lift it's in, if it's about one.
+    pub fn problems_in(&self, day: &Day) -> Vec<(Option<usize>, NotationError)> {
+        let mut problems = Vec::new();
+        if day.lifts.is_empty() {
+            problems.push((None, NotationError::new("A day needs lifts", &day.to_string())));
+        }
+        let mut names = BTreeSet::new();
+        day.lifts
+            .iter()
+            .enumerate()
+            .filter(|(_, lift)| !names.insert(lift.id()))
+            .for_each(|(index, lift)| {
+                problems.push((
+                    Some(index),
+                    NotationError::new("A day can't have two lifts with the same name", &lift.name),
+                ))
+            });
+        day.lifts
+            .iter()
+            .enumerate()
+            .filter(|(_, lift)| matches!(lift.weight, WeightScheme::LinearBasedOnPrevious { .. }))
+            .filter(|(_, lift)| !self.weights.contains_key(&lift.id()))
+            .for_each(|(index, lift)| {
+                problems.push((
+                    Some(index),
+                    NotationError::new(
+                        &format!(
+                            "A lift that adds to its previous weight needs a starting weight, like '{} = 45' after the days",
+                            quote(&lift.name, &NAME_SEPARATORS)
+                        ),
+                        &lift.name,
+                    ),
+                ))
+            });
+        problems
+    }
+
+    /// What else is wrong with the day, once the reference weights are known: a lift's weight
+    /// starts out below zero, as it's rounded, without the lift being done with bodyweight
+    /// (which would make it assisted). Each problem comes with the index of the lift it's in.
+    pub fn weights_below_zero_in(&self, day: &Day) -> Vec<(Option<usize>, NotationError)> {
+        let mut problems = Vec::new();
+        day.lifts
+            .iter()
+            .enumerate()
+            .filter(|(_, lift)| lift.done_with() != Equipment::Bodyweight)
+            .flat_map(|(index, lift)| {
+                [lift.top_sets()]
+                    .into_iter()
+                    .chain(lift.back_off.clone())
+                    .map(move |group| (index, group))
+            })
+            .for_each(|(index, group)| {
+                let lift = &day.lifts[index];
+                if let WeightScheme::BasedOnReference {
+                    multiplier,
+                    offset,
+                    reference,
+                } = &group.weight
+                {
+                    let reference_weight = self.starting_weight_of(reference);
+                    let weight =
+                        self.rounding_for(lift).round(multiplier * reference_weight + offset);
+                    if weight < 0.0 {
+                        problems.push((
+                            Some(index),
+                            NotationError::new(
+                                &format!("The weight starts out below zero, at {weight}"),
+                                &group.weight.to_string(),
+                            ),
+                        ))
+                    }
+                }
+            });
+        problems
+    }
+
+    /// What's probably a mistake in the day of this program, even though it can be done:
+    /// a rep range that goes from more reps to fewer, no lift in it that progresses, or
+    /// (see `unreachable_weights_in()`) a weight the bar can't be loaded to. Each warning
+    /// comes with the index of the lift it's in, if it's about one.
+    pub fn warnings_in(&self, day: &Day) -> Vec<(Option<usize>, NotationError)> {
+        let mut warnings: Vec<(Option<usize>, NotationError)> = day
+            .lifts
+            .iter()
+            .enumerate()
+            .flat_map(|(index, lift)| {
+                lift.all_sets().iter().find_map(|set| match *set.without_effort() {
+                    Set::Range {
+                        minimum_reps,
+                        maximum_reps,
+                    } if minimum_reps > maximum_reps => Some((index, minimum_reps, maximum_reps)),
+                    _ => None,
                 })
-                .ok_or(anyhow!(error))?,
-            weights: Default::default(),
-        })
+            })
+            .map(|(index, minimum_reps, maximum_reps)| {
+                (
+                    Some(index),
+                    NotationError::new(
+                        "A rep range must go from fewer reps to more",
+                        &format!("{minimum_reps}-{maximum_reps}"),
+                    )
+                    .suggesting(Some(format!("{maximum_reps}-{minimum_reps}"))),
+                )
+            })
+            .collect();
+        if !day.lifts.is_empty() && !day.lifts.iter().any(|lift| lift.progresses()) {
+            warnings.push((None, NotationError::new("Nothing in this day progresses", &day.name)));
+        }
+        warnings.extend(self.unreachable_weights_in(day));
+        warnings
+    }
+
+    /// The barbell lifts of the day that start out at a weight the bar and plates can't be
+    /// loaded to, or go up from it to one: the next weight their rounding makes, or that
+    /// adding to it makes, by their index. Weights under the bar are left to be done with
+    /// the empty bar.
+    fn unreachable_weights_in(&self, day: &Day) -> Vec<(Option<usize>, NotationError)> {
+        let inventory = self.settings.plate_inventory();
+        let unit = inventory.unit;
+        day.lifts
+            .iter()
+            .enumerate()
+            .filter(|(_, lift)| lift.done_with() == Equipment::Barbell)
+            .flat_map(|(index, lift)| {
+                [lift.top_sets()]
+                    .into_iter()
+                    .chain(lift.back_off.clone())
+                    .map(move |group| (index, lift, group))
+            })
+            .flat_map(|(index, lift, group)| {
+                let rounding = self.rounding_for(lift);
+                let (weight, next) = match group.weight {
+                    WeightScheme::BasedOnReference {
+                        multiplier,
+                        offset,
+                        ref reference,
+                    } => {
+                        let weight =
+                            rounding.round(multiplier * self.starting_weight_of(reference) + offset);
+                        (weight, weight + rounding.increment)
+                    }
+                    WeightScheme::LinearBasedOnPrevious { amount_to_increase } => {
+                        let weight = *self.weights.get(&lift.id())?;
+                        (weight, rounding.round(weight + amount_to_increase))
+                    }
+                    _ => return None,
+                };
+                let (weight, loading) = [weight, next]
+                    .into_iter()
+                    .filter(|weight| *weight >= inventory.bar)
+                    .map(|weight| (weight, inventory.load(weight)))
+                    .find(|(_, loading)| !loading.as_ref().is_ok_and(|it| it.is_exact()))?;
+                let nearest = match loading {
+                    Ok(loading) => format!(" (the nearest is {} {unit})", loading.weight),
+                    Err(_) => String::new(),
+                };
+                Some((
+                    Some(index),
+                    NotationError::new(
+                        &format!(
+                            "This lift comes to {weight} {unit}, which the bar can't be loaded to with the plates there are{nearest}"
+                        ),
+                        &group.weight.to_string(),
+                    ),
+                ))
+            })
+            .collect()
+    }
+
+    /// Starting weights given to lifts that don't add to their previous weight, which are
+    /// never used.
+    pub fn unused_starting_weights(&self) -> Vec<NotationError> {
+        let mut named = BTreeSet::new();
+        self.days
+            .iter()
+            .flat_map(|day| day.lifts.iter())
+            .filter(|lift| self.weights.contains_key(&lift.id()))
+            .filter(|lift| !matches!(lift.weight, WeightScheme::LinearBasedOnPrevious { .. }))
+            .filter(|lift| named.insert(lift.id()))
+            .map(|lift| {
+                NotationError::new(
+                    "This lift doesn't add to its previous weight, so never uses a starting weight",
+                    &lift.name,
+                )
+            })
+            .collect()
+    }
+
+    /// Named reference weights that no lift is based on, which are never used.
+    pub fn unused_references(&self) -> Vec<NotationError> {
+        let used: BTreeSet<String> = self
+            .days
+            .iter()
+            .flat_map(|day| day.lifts.iter())
+            .flat_map(|lift| [lift.top_sets()].into_iter().chain(lift.back_off.clone()))
+            .flat_map(|group| match group.weight {
+                WeightScheme::BasedOnReference { reference, .. } => reference,
+                _ => None,
+            })
+            .collect();
+        self.starting_reference_weights
+            .keys()
+            .filter(|reference| !used.contains(*reference))
+            .map(|reference| {
+                NotationError::new("No lift is based on this reference weight", reference)
+            })
+            .collect()
+    }
+
+    /// What the reference weight starts out at, or the main one, if it's not named.
+    fn starting_weight_of(&self, reference: &Option<String>) -> f64 {
+        reference
+            .as_ref()
+            .and_then(|reference| self.starting_reference_weights.get(reference))
+            .map_or(self.starting_reference_weight, |it| it.weight(self.starting_reference_weight))
+    }
+
+    fn rounding_for(&self, lift: &Lift) -> Rounding {
+        lift.rounding
+            .or(self.settings.rounding)
+            .unwrap_or(self.settings.unit.unwrap_or_default().default_rounding())
+    }
+
+    /// Whether the reference weight is only used by lifts done with bodyweight.
+    fn only_bodyweight_lifts_use(&self, reference: &str) -> bool {
+        let mut users = self
+            .days
+            .iter()
+            .flat_map(|day| day.lifts.iter())
+            .filter(|lift| {
+                [lift.top_sets()]
+                    .into_iter()
+                    .chain(lift.back_off.clone())
+                    .any(|group| match &group.weight {
+                        WeightScheme::BasedOnReference { reference: Some(name), .. } => {
+                            name == reference
+                        }
+                        _ => false,
+                    })
+            })
+            .peekable();
+        users.peek().is_some() && users.all(|lift| lift.done_with() == Equipment::Bodyweight)
+    }
+
+    /// notation is like '135, squat = 1.35r, bench = 135'
+    fn parse_references(
+        notation: &str,
+    ) -> Result<(f64, BTreeMap<String, StartingReference>)> {
+        let mut split = split_unquoted(notation, ",").into_iter();
+        let reference_weight = split.next().unwrap_or_default();
+        let reference_weight = decimal(reference_weight.trim())
+            .map_err(|_| NotationError::new("Cannot parse reference weight", reference_weight))?;
+        let mut references = BTreeMap::new();
+        split.try_for_each(|name_and_weight| -> Result<()> {
+            let (name, weight) = split_once_unquoted(name_and_weight, "=")
+                .ok_or(NotationError::new("Cannot parse reference weight", name_and_weight))?;
+            references.insert(
+                unquote(name)?,
+                StartingReference::parse(weight.trim())
+                    .notation("Cannot parse reference weight", weight)?,
+            );
+            Ok(())
+        })?;
+        Ok((reference_weight, references))
+    }
+
+    /// The names of every reference weight used by the lifts of this program.
+    pub fn references(&self) -> BTreeSet<String> {
+        self.days
+            .iter()
+            .flat_map(|day| day.lifts.iter())
+            .flat_map(|lift| {
+                std::iter::once(&lift.weight).chain(lift.back_off.iter().map(|group| &group.weight))
+            })
+            .flat_map(|weight| match weight {
+                WeightScheme::BasedOnReference {
+                    reference: Some(reference),
+                    ..
+                } => Some(reference.clone()),
+                _ => None,
+            })
+            .chain(self.starting_reference_weights.keys().cloned())
+            .collect()
+    }
+
+    /// notation is like 'Face Pull = 30 !! Cable Curl = 20'
+    fn parse_weights(
+        notation: &str,
+        delimiter: &str,
+        days: &[Day],
+    ) -> Result<HashMap<LiftId, f64>> {
+        let mut weights = HashMap::new();
+        split_unquoted(notation, delimiter)
+            .into_iter()
+            .filter(|it| !it.trim().is_empty())
+            .try_for_each(|name_and_weight| -> Result<()> {
+                let (name, weight) = split_once_unquoted(name_and_weight, "=")
+                    .ok_or(NotationError::new("Cannot parse starting weight", name_and_weight))?;
+                let weight = decimal(weight.trim())
+                    .map_err(|_| NotationError::new("Cannot parse starting weight", weight))?;
+                let unquoted = unquote(name)?;
+                let id = LiftId::new(&unquoted);
+                let named = days
+                    .iter()
+                    .flat_map(|day| day.lifts.iter())
+                    .any(|lift| lift.id() == id);
+                if !named {
+                    let names: Vec<&str> = days
+                        .iter()
+                        .flat_map(|day| day.lifts.iter())
+                        .map(|lift| lift.name.as_str())
+                        .collect();
+                    return Err(NotationError::new("No lift to give a starting weight to", name)
+                        .suggesting(suggest(&unquoted, &names).map(|it| quote(it, &NAME_SEPARATORS)))
+                        .into());
+                }
+                weights.insert(id, weight);
+                Ok(())
+            })?;
+        Ok(weights)
+    }
+
+    pub fn start(self) -> Program {
+        let reference_weights: BTreeMap<String, f64> = self
+            .references()
+            .into_iter()
+            .map(|reference| {
+                let weight = self
+                    .starting_reference_weights
+                    .get(&reference)
+                    .map(|it| match self.only_bodyweight_lifts_use(&reference) {
+                        true => it.weight_added(self.starting_reference_weight),
+                        false => it.weight(self.starting_reference_weight),
+                    })
+                    .unwrap_or(self.starting_reference_weight);
+                (reference, weight)
+            })
+            .collect();
+        Program {
+            name: self.name,
+            reference_weight: self.starting_reference_weight,
+            starting_reference_weight: self.starting_reference_weight,
+            reference_weights: reference_weights.clone(),
+            starting_reference_weights: reference_weights,
+            weights: self.weights,
+            days: self.days,
+            current_day: 0,
+            current_cycle_attempt_results: vec![],
+            workouts_completed: 0,
+            settings: self.settings,
+            lift_progressions: Default::default(),
+            reference_progressions: Default::default(),
+            bodyweight: None,
+            template: None,
+        }
     }
 }
 
 impl Program {
     pub fn complete_workout(self, results: &[LiftAttemptResult]) -> Program {
-        self.save_results(results)
-            .increment_non_reference_weights()
-            .increment_reference()
+        self.complete_workout_with_deloads(results).0
+    }
+
+    /// Like `complete_workout()`, also giving the deloads that completing the workout caused.
+    pub fn complete_workout_with_deloads(
+        self,
+        results: &[LiftAttemptResult],
+    ) -> (Program, Vec<Deload>) {
+        let mut deloads = Vec::new();
+        let graduating = self.graduating_lifts(results);
+        let program = self
+            .save_results(results)
+            .increment_non_reference_weights(&mut deloads)
+            .increment_weightless_lifts()
+            .increment_reference(&mut deloads)
+            .graduate(graduating)
             .increment_count()
-            .increment_day()
+            .increment_day();
+        (program, deloads)
     }
 
     pub fn next_workout(&self) -> Vec<LiftAttempt> {
-        self.days[self.current_day]
-            .lifts
+        let day = &self.days[self.current_day];
+        day.lifts
             .iter()
-            .map(|lift| LiftAttempt {
-                lift: lift.clone(),
-                weight: match lift.weight {
-                    WeightScheme::BasedOnReference { .. } => Some(self.reference_weight),
-                    WeightScheme::Any => None,
-                    WeightScheme::None => None,
-                    WeightScheme::LinearBasedOnPrevious { .. } => {
-                        self.weights.get(lift).map(|it| *it)
-                    }
-                },
+            .enumerate()
+            .map(|(index, lift)| {
+                let prescription = self.prescription(lift);
+                LiftAttempt {
+                    superset: day.superset_of(index),
+                    weight: match lift.weight {
+                        WeightScheme::BasedOnReference { ref reference, .. } => {
+                            Some(self.reference_weight_for(reference))
+                        }
+                        WeightScheme::Any => None,
+                        WeightScheme::None => None,
+                        WeightScheme::Bodyweight { .. } => None,
+                        WeightScheme::LinearBasedOnPrevious { .. } => {
+                            self.weight_of(lift).map(|it| it * prescription.weight_factor)
+                        }
+                    },
+                    back_off_weights: lift
+                        .back_off
+                        .iter()
+                        .map(|group| match &group.weight {
+                            WeightScheme::BasedOnReference { reference, .. } => {
+                                Some(self.reference_weight_for(reference))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                    lift: match lift.weight {
+                        WeightScheme::BasedOnReference { .. } => {
+                            prescription.lift.scaled(prescription.weight_factor)
+                        }
+                        _ => prescription.lift,
+                    },
+                    rounding: self.rounding_for(lift),
+                    unit: self.unit(),
+                    note: prescription.note,
+                    plates: None,
+                    warmups: Vec::new(),
+                    bodyweight: self.bodyweight,
+                }
+            })
+            .map(|attempt| LiftAttempt {
+                plates: self.plates_for(&attempt),
+                warmups: self.warmups_for(&attempt),
+                ..attempt
             })
             .collect()
     }
 
-    fn increment_reference(mut self) -> Self {
+    /// The bar and plates this program's barbell lifts are loaded with.
+    pub fn plate_inventory(&self) -> PlateInventory {
+        self.settings.plate_inventory()
+    }
+
+    /// Lifts based on a reference weight are warmed up for the way the program says,
+    /// others only if they say how.
+    fn warmups_for(&self, attempt: &LiftAttempt) -> Vec<WarmupSet> {
+        let scheme = match (&attempt.lift.warmup, &attempt.lift.weight) {
+            (Some(scheme), _) => scheme.clone(),
+            (None, WeightScheme::BasedOnReference { .. }) => {
+                match &self.settings.warmup {
+                    Some(scheme) => scheme.clone(),
+                    None => return Vec::new(),
+                }
+            }
+            (None, _) => return Vec::new(),
+        };
+        let rounding = attempt.rounding.down();
+        let weight = match attempt.prescribed_weight() {
+            Some(weight) => weight,
+            None => return Vec::new(),
+        };
+        match attempt.lift.done_with() {
+            Equipment::Barbell => {
+                let inventory = self.plate_inventory();
+                scheme.ladder(
+                    weight,
+                    Some(inventory.bar),
+                    |it| {
+                        let rounded = rounding.round(it);
+                        inventory.load(rounded).map_or(rounded, |it| it.weight)
+                    },
+                    attempt.unit,
+                )
+            }
+            _ => scheme.ladder(weight, None, |it| rounding.round(it), attempt.unit),
+        }
+    }
+
+    fn plates_for(&self, attempt: &LiftAttempt) -> Option<PlateLoading> {
+        match attempt.lift.done_with() {
+            Equipment::Barbell => attempt
+                .prescribed_weight()
+                .and_then(|weight| self.plate_inventory().load(weight).ok()),
+            _ => None,
+        }
+    }
+
+    /// The lift as its progression has it attempted next.
+    /// Lifts based on a reference weight follow where that reference is in its progression.
+    /// Lifts without a weight to progress, bodyweight schemes included, only progress if they say how.
+    fn prescription(&self, lift: &Lift) -> Prescription {
+        match &lift.weight {
+            WeightScheme::BasedOnReference { reference, .. } => self
+                .progression_scheme(lift)
+                .progression(self.unit().increment(), self.rounding_for(lift))
+                .prescribe(lift, &self.reference_progression(reference)),
+            WeightScheme::LinearBasedOnPrevious { amount_to_increase } => self
+                .progression_scheme(lift)
+                .progression(*amount_to_increase, self.rounding_for(lift))
+                .prescribe(lift, &self.lift_progression(lift)),
+            WeightScheme::Any | WeightScheme::None | WeightScheme::Bodyweight { .. } => {
+                match lift.own_progression() {
+                    Some(scheme) => scheme
+                        .progression(self.unit().increment(), self.rounding_for(lift))
+                        .prescribe(lift, &self.lift_progression(lift)),
+                    None => Prescription {
+                        lift: lift.clone(),
+                        weight_factor: 1.0,
+                        note: None,
+                    },
+                }
+            }
+        }
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.settings.unit.unwrap_or_default()
+    }
+
+    fn rounding_for(&self, lift: &Lift) -> Rounding {
+        lift.rounding
+            .or(self.settings.rounding)
+            .unwrap_or(self.unit().default_rounding())
+    }
+
+    fn progression_scheme(&self, lift: &Lift) -> ProgressionScheme {
+        lift.own_progression()
+            .or(self.settings.progression.clone())
+            .unwrap_or(ProgressionScheme::Double { increment: None })
+    }
+
+    /// The progression of the first lift using the reference which has one, or the program's.
+    fn reference_progression_scheme(&self, reference: &Option<String>) -> ProgressionScheme {
+        self.indexes_of_past_attempts_that_use_reference(reference)
+            .into_iter()
+            .find_map(|(day_index, lift_index)| {
+                self.days[day_index].lifts[lift_index].own_progression()
+            })
+            .or(self.settings.progression.clone())
+            .unwrap_or(ProgressionScheme::Double { increment: None })
+    }
+
+    fn reference_progression(&self, reference: &Option<String>) -> ProgressionState {
+        self.reference_progressions
+            .get(reference)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The weight the lift is at, kept under its id in this program's days (see
+    /// `Lift::id_in()`), or until it has one of its own, under its `LiftId`.
+    fn weight_of(&self, lift: &Lift) -> Option<f64> {
+        self.weights.get(&lift.id_in(&self.days)).or(self.weights.get(&lift.id())).copied()
+    }
+
+    /// Like `weight_of()`, where the lift is in its progression.
+    fn lift_progression(&self, lift: &Lift) -> ProgressionState {
+        self.lift_progressions
+            .get(&lift.id_in(&self.days))
+            .or(self.lift_progressions.get(&lift.id()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The current weight of the named reference, or of the main one if there is no name.
+    pub fn reference_weight_for(&self, reference: &Option<String>) -> f64 {
+        reference
+            .as_ref()
+            .and_then(|name| self.reference_weights.get(name))
+            .copied()
+            .unwrap_or(self.reference_weight)
+    }
+
+    fn increment_reference(mut self, deloads: &mut Vec<Deload>) -> Self {
         if !self.is_last_day() {
             return self;
         }
-        if self.are_all_completed(self.indexes_of_past_attempts_that_are_reference_lifts()) {
-            self.reference_weight = self.reference_weight + 5;
-        }
+        let references: Vec<Option<String>> = std::iter::once(None)
+            .chain(self.reference_weights.keys().cloned().map(Some))
+            .collect();
+        references.into_iter().for_each(|reference| {
+            let past_attempt_indexes = self.indexes_of_past_attempts_that_use_reference(&reference);
+            if past_attempt_indexes.is_empty() {
+                return;
+            }
+            let results = self.past_attempt_results(past_attempt_indexes);
+            let weight = self.reference_weight_for(&reference);
+            let state = self.reference_progression(&reference);
+            let rounding = self
+                .settings
+                .rounding
+                .unwrap_or(self.unit().default_rounding());
+            let progressed = self
+                .reference_progression_scheme(&reference)
+                .progression(self.unit().increment(), rounding)
+                .progress(weight, &state, &results);
+            let what = match &reference {
+                Some(name) => format!("'{name}' reference weight"),
+                None => "reference weight".to_string(),
+            };
+            let (weight, state) =
+                self.check_for_stall(what, weight, &state, progressed, rounding, deloads);
+            match &reference {
+                Some(name) => {
+                    self.reference_weights.insert(name.clone(), weight);
+                }
+                None => self.reference_weight = weight,
+            }
+            self.reference_progressions.insert(reference, state);
+        });
         self
     }
 
-    fn are_all_completed(&self, past_attempt_indexes: Vec<(usize, usize)>) -> bool {
-        past_attempt_indexes.iter().all(|(day_index, lift_index)| {
-            match self.current_cycle_attempt_results[*day_index][*lift_index] {
-                LiftAttemptResult::Completed {
-                    completed_maximum_reps,
-                } => completed_maximum_reps,
-                _ => false,
-            }
-        })
+    /// Each past attempt as it was prescribed, alongside its result.
+    fn past_attempt_results(
+        &self,
+        past_attempt_indexes: Vec<(usize, usize)>,
+    ) -> Vec<(Lift, LiftAttemptResult)> {
+        past_attempt_indexes
+            .into_iter()
+            .map(|(day_index, lift_index)| {
+                let lift = &self.days[day_index].lifts[lift_index];
+                let result = self
+                    .current_cycle_attempt_results
+                    .get(day_index)
+                    .and_then(|day| day.get(lift_index))
+                    .cloned()
+                    .unwrap_or(LiftAttemptResult::NotCompleted);
+                (self.prescription(lift).lift, result)
+            })
+            .collect()
     }
 
-    fn indexes_of_past_attempts_that_are_reference_lifts(&self) -> Vec<(usize, usize)> {
+    fn indexes_of_past_attempts_that_use_reference(
+        &self,
+        reference: &Option<String>,
+    ) -> Vec<(usize, usize)> {
         let mut past_attempt_indexes: Vec<(usize, usize)> = Vec::new();
         self.days.iter().enumerate().for_each(|(day_index, day)| {
             day.lifts.iter().enumerate().for_each(|(lift_index, lift)| {
-                if matches!(lift.weight, WeightScheme::BasedOnReference { .. }) {
+                if matches!(&lift.weight, WeightScheme::BasedOnReference { reference: it, .. } if it == reference)
+                {
                     past_attempt_indexes.push((day_index, lift_index))
                 }
             })
@@ -130,108 +1050,311 @@ impl Program {
         self
     }
 
-    fn increment_non_reference_weights(mut self) -> Self {
+    fn increment_non_reference_weights(mut self, deloads: &mut Vec<Deload>) -> Self {
+        let lifts = self.days[self.current_day].lifts.clone();
+        lifts.iter().enumerate().for_each(|(index, lift)| {
+            if let WeightScheme::LinearBasedOnPrevious { amount_to_increase } = lift.weight {
+                let prescription = self.prescription(lift);
+                let result = self.current_cycle_attempt_results[self.current_day][index].clone();
+                let stored_weight = self.weight_of(lift);
+                // A weight other than the prescribed one only counts once it was lifted.
+                let weight = result
+                    .weight_used()
+                    .filter(|_| result.is_completed(&prescription.lift))
+                    .map(|it| it / prescription.weight_factor)
+                    .or(stored_weight);
+                let state = self.lift_progression(lift);
+                let progressed = self
+                    .progression_scheme(lift)
+                    .progression(amount_to_increase, self.rounding_for(lift))
+                    .progress(
+                        weight.unwrap_or_default(),
+                        &state,
+                        &[(prescription.lift, result)],
+                    );
+                let (new_weight, state) = self.check_for_stall(
+                    lift.name.clone(),
+                    weight.unwrap_or_default(),
+                    &state,
+                    progressed,
+                    self.rounding_for(lift),
+                    deloads,
+                );
+                let id = lift.id_in(&self.days);
+                if weight.is_some() || new_weight > 0.0 {
+                    self.weights.insert(id.clone(), new_weight);
+                }
+                self.lift_progressions.insert(id, state);
+            }
+        });
+        self
+    }
+
+    /// Moves lifts without a weight to progress (like bodyweight or timed ones) along their
+    /// progression, if they have one, like to more reps or seconds.
+    fn increment_weightless_lifts(mut self) -> Self {
+        let lifts = self.days[self.current_day].lifts.clone();
+        lifts.iter().enumerate().for_each(|(index, lift)| {
+            let weightless = matches!(
+                lift.weight,
+                WeightScheme::Any | WeightScheme::None | WeightScheme::Bodyweight { .. }
+            );
+            if let (true, Some(scheme)) = (weightless, lift.own_progression()) {
+                let prescription = self.prescription(lift);
+                let result = self.current_cycle_attempt_results[self.current_day][index].clone();
+                let (_, state) = scheme
+                    .progression(self.unit().increment(), self.rounding_for(lift))
+                    .progress(
+                        0.0,
+                        &self.lift_progression(lift),
+                        &[(prescription.lift, result)],
+                    );
+                self.lift_progressions.insert(lift.id_in(&self.days), state);
+            }
+        });
+        self
+    }
+
+    /// The lifts of the day that reached their graduation, each with the variant it becomes.
+    fn graduating_lifts(&self, results: &[LiftAttemptResult]) -> Vec<(Lift, Lift)> {
         self.days[self.current_day]
             .lifts
             .iter()
-            .enumerate()
-            .for_each(|(index, lift)| {
-                if let WeightScheme::LinearBasedOnPrevious { amount_to_increase } = lift.weight {
-                    if self.current_cycle_attempt_results[self.current_day][index]
-                        == (LiftAttemptResult::Completed {
-                            completed_maximum_reps: true,
-                        })
-                    {
-                        self.weights.insert(
-                            lift.clone(),
-                            self.weights
-                                .get(lift)
-                                .map(|it| it + amount_to_increase)
-                                .or(Some(amount_to_increase))
-                                .unwrap(),
-                        );
-                    }
+            .zip(results)
+            .filter_map(|(lift, result)| {
+                let graduation = lift.graduation.as_ref()?;
+                if !result.reached_reps(&self.prescription(lift).lift, graduation.reps) {
+                    return None;
                 }
+                let variant = Lift {
+                    name: graduation.name.clone(),
+                    weight: graduation.weight.clone(),
+                    progression: None,
+                    graduation: None,
+                    ..lift.clone()
+                };
+                Some((lift.clone(), variant))
+            })
+            .collect()
+    }
+
+    /// Swaps each lift for the variant it graduated to, everywhere in the program.
+    fn graduate(mut self, graduating: Vec<(Lift, Lift)>) -> Self {
+        graduating.into_iter().for_each(|(lift, variant)| {
+            self.lift_progressions.remove(&lift.id_in(&self.days));
+            self.days.iter_mut().for_each(|day| {
+                day.lifts
+                    .iter_mut()
+                    .filter(|it| **it == lift)
+                    .for_each(|it| *it = variant.clone())
             });
+            if let Some(starting_weight) =
+                lift.graduation.as_ref().and_then(|it| it.starting_weight)
+            {
+                self.weights.insert(variant.id_in(&self.days), starting_weight);
+            }
+        });
         self
     }
+
+    /// Counts a stall if the progression went nowhere, and deloads once there have been enough.
+    fn check_for_stall(
+        &self,
+        what: String,
+        weight: f64,
+        state: &ProgressionState,
+        (next_weight, next_state): (f64, ProgressionState),
+        rounding: Rounding,
+        deloads: &mut Vec<Deload>,
+    ) -> (f64, ProgressionState) {
+        if !state.is_stalled(weight, &next_state, next_weight) {
+            return (next_weight, ProgressionState { stalls: 0, ..next_state });
+        }
+        let stalls = state.stalls + 1;
+        match self.settings.deload.clone().unwrap_or_default() {
+            DeloadRule::After {
+                stalls: stalls_before_deload,
+                percent,
+            } if stalls >= stalls_before_deload => {
+                let deloaded = rounding
+                    .down()
+                    .round(weight * (100 - percent) as f64 / 100.0);
+                if deloaded < weight {
+                    deloads.push(Deload {
+                        what,
+                        from: weight,
+                        to: deloaded,
+                        unit: self.unit(),
+                        stalls,
+                    });
+                }
+                (deloaded, ProgressionState { stalls: 0, ..next_state })
+            }
+            _ => (next_weight, ProgressionState { stalls, ..next_state }),
+        }
+    }
+
+    /// The same program, with every weight measured in another unit.
+    /// Weights are rounded to what that unit's plates allow, and so are the increments and
+    /// offsets written in the lifts. Rounding is kept at the same precision, as near as can be.
+    pub fn convert_units(self, unit: Unit) -> Program {
+        let from = self.unit();
+        if from == unit {
+            return self;
+        }
+        let weight = |weight: f64| -> f64 {
+            Rounding {
+                increment: unit.increment(),
+                direction: RoundingDirection::Nearest,
+            }
+            .round(from.convert(weight, unit))
+        };
+        let increment = |increment: f64| -> f64 { weight(increment).max(unit.increment()) };
+        let rounding = |rounding: Rounding| -> Rounding {
+            Rounding {
+                increment: Rounding {
+                    increment: 0.25,
+                    direction: RoundingDirection::Nearest,
+                }
+                .round(from.convert(rounding.increment, unit))
+                .max(0.25),
+                ..rounding
+            }
+        };
+        let convert_scheme = |scheme: &WeightScheme| -> WeightScheme {
+            match scheme {
+                WeightScheme::BasedOnReference {
+                    multiplier,
+                    offset,
+                    reference,
+                } => WeightScheme::BasedOnReference {
+                    multiplier: *multiplier,
+                    offset: weight(*offset),
+                    reference: reference.clone(),
+                },
+                WeightScheme::LinearBasedOnPrevious { amount_to_increase } => {
+                    WeightScheme::LinearBasedOnPrevious {
+                        amount_to_increase: increment(*amount_to_increase),
+                    }
+                }
+                WeightScheme::Bodyweight { added } => WeightScheme::Bodyweight {
+                    added: weight(*added),
+                },
+                other => other.clone(),
+            }
+        };
+        let convert_lift = |lift: &Lift| -> Lift {
+            Lift {
+                weight: convert_scheme(&lift.weight),
+                back_off: lift
+                    .back_off
+                    .iter()
+                    .map(|group| SetGroup {
+                        weight: convert_scheme(&group.weight),
+                        ..group.clone()
+                    })
+                    .collect(),
+                progression: lift
+                    .progression
+                    .as_ref()
+                    .map(|it| it.map_increment(increment)),
+                rounding: lift.rounding.map(rounding),
+                graduation: lift.graduation.as_ref().map(|graduation| Graduation {
+                    weight: convert_scheme(&graduation.weight),
+                    starting_weight: graduation.starting_weight.map(weight),
+                    ..graduation.clone()
+                }),
+                ..lift.clone()
+            }
+        };
+        Program {
+            reference_weight: weight(self.reference_weight),
+            starting_reference_weight: weight(self.starting_reference_weight),
+            reference_weights: self
+                .reference_weights
+                .iter()
+                .map(|(name, it)| (name.clone(), weight(*it)))
+                .collect(),
+            starting_reference_weights: self
+                .starting_reference_weights
+                .iter()
+                .map(|(name, it)| (name.clone(), weight(*it)))
+                .collect(),
+            days: self
+                .days
+                .iter()
+                .map(|day| Day {
+                    lifts: day.lifts.iter().map(convert_lift).collect(),
+                    ..day.clone()
+                })
+                .collect(),
+            weights: self
+                .weights
+                .iter()
+                .map(|(id, it)| (id.clone(), weight(*it)))
+                .collect(),
+            current_cycle_attempt_results: self
+                .current_cycle_attempt_results
+                .iter()
+                .map(|day| {
+                    day.iter()
+                        .map(|result| match result {
+                            LiftAttemptResult::Performed {
+                                reps,
+                                parts,
+                                sides,
+                                time_taken,
+                                weight: used,
+                                back_off_weights,
+                                rpe,
+                            } => LiftAttemptResult::Performed {
+                                reps: reps.clone(),
+                                parts: parts.clone(),
+                                sides: sides.clone(),
+                                time_taken: time_taken.clone(),
+                                weight: used.map(weight),
+                                back_off_weights: back_off_weights
+                                    .iter()
+                                    .map(|it| it.map(weight))
+                                    .collect(),
+                                rpe: *rpe,
+                            },
+                            other => other.clone(),
+                        })
+                        .collect()
+                })
+                .collect(),
+            settings: Settings {
+                rounding: self.settings.rounding.map(rounding),
+                unit: Some(unit),
+                // Plates in another unit are other plates, so go back to the standard ones.
+                bar: None,
+                plates: None,
+                ..self.settings.clone()
+            },
+            bodyweight: self.bodyweight.map(weight),
+            ..self
+        }
+    }
+
+    /// The same program, knowing the lifter weighs this much now.
+    pub fn log_bodyweight(self, bodyweight: f64) -> Program {
+        Program {
+            bodyweight: Some(bodyweight),
+            ..self
+        }
+    }
+
     fn increment_count(mut self) -> Self {
         self.workouts_completed += 1;
         self
     }
 }
 
-pub fn start_gzcl_4day(reference_weight: usize) -> Program {
-    fn lift(str: &str) -> Lift {
-        Lift::parse(str).unwrap()
-    }
-    let gzcl_4day = NewProgram {
-        name: "GZCL-based 4-day cycle (Pull, Push, Legs, Core)".to_string(),
-        starting_reference_weight: reference_weight,
-        days: vec![
-            Day {
-                name: "Pull".to_string(),
-                lifts: vec![
-                    lift("Weighted Pullup -> 4x3,1x3+ @ 0.5r-30"),
-                    lift("Pullup -> 3x7+"),
-                    lift("Barbell Row -> 3x10 @ 0.65r"),
-                    lift("Face Pull -> 2x15,1x15-25 @ add20"),
-                    lift("Cable Curl -> 2x15,1x15-25 @ add20"),
-                ],
-            },
-            Day {
-                name: "Push".to_string(),
-                lifts: vec![
-                    lift("Bench press -> 4x3,1x3+ @ 1r"),
-                    lift("Overhead press -> 3x10 @ 0.5r"),
-                    lift("Incline bench press -> 3x10 @ 0.6r"),
-                    lift("Pushup -> 3x15+"),
-                    lift("Tricep Cable Pressdown -> 2x15,1x15-25 @ add20"),
-                ],
-            },
-            Day {
-                name: "Legs".to_string(),
-                lifts: vec![
-                    lift("Squat -> 4x3,1x3+ @ 1.35r"),
-                    lift("Deadlift -> 3x8 @ 1.25r"),
-                    lift("Romanian Deadlift -> 3x10 @ 0.675r"),
-                    lift("Leg press -> 2x15,1x15-25 @ add30"),
-                    lift("Standing dumbbell calf raise -> 2x15,1x15-25 @ add20"),
-                ],
-            },
-            Day {
-                name: "Core".to_string(),
-                lifts: vec![
-                    lift("Plank -> 1x30s @ any"),
-                    lift("Ab Rollout -> 3xAny"),
-                    lift("Cable Core Press -> 3xAny @ any"),
-                    lift("Bent-knee reverse hyperextension -> 3xAny @ any"),
-                    lift("Knee raises -> 3xAny"),
-                    lift("Leg extensions -> 3xAny @ any"),
-                ],
-            },
-        ],
-        weights: HashMap::from([
-            (lift("Face Pull -> 2x15,1x15-25 @ add20"), 30),
-            (lift("Cable Curl -> 2x15,1x15-25 @ add20"), 20),
-            (lift("Tricep Cable Pressdown -> 2x15,1x15-25 @ add20"), 20),
-            (lift("Leg press -> 2x15,1x15-25 @ add30"), 45),
-            (
-                lift("Standing dumbbell calf raise -> 2x15,1x15-25 @ add20"),
-                45,
-            ),
-        ]),
-    };
-    Program {
-        name: gzcl_4day.name,
-        reference_weight: gzcl_4day.starting_reference_weight,
-        starting_reference_weight: gzcl_4day.starting_reference_weight,
-        weights: gzcl_4day.weights,
-        days: gzcl_4day.days,
-        current_day: 0,
-        current_cycle_attempt_results: vec![],
-        workouts_completed: 0,
-    }
+pub fn start_gzcl_4day(reference_weight: f64) -> Program {
+    let mut gzcl_4day = templates::find("gzcl-4day").unwrap().new_program();
+    gzcl_4day.starting_reference_weight = reference_weight;
+    gzcl_4day.start()
 }
 
 #[cfg(test)]
@@ -247,47 +1370,381 @@ mod tests {
             assert_eq!(
                 NewProgram {
                     name: "GZCL 4-Day".to_string(),
-                    starting_reference_weight: 135,
+                    starting_reference_weight: 135.0,
+                    starting_reference_weights: Default::default(),
                     days: vec![
                         Day::parse("Day One | Bench press -> 3x5,1x5-6,1x6+ @ 2r | Pullup -> 3x5,1x5-6,1x6+").unwrap(),
                         Day::parse("Day Two | Some lift -> 3xAny").unwrap(),
                     ],
-                    weights: Default::default(), // todo
+                    weights: Default::default(),
+                    settings: Default::default(),
                 },
                 NewProgram::parse("GZCL 4-Day !!! 135 !!! Day One | Bench press -> 3x5,1x5-6,1x6+ @ 2r | Pullup -> 3x5,1x5-6,1x6+ !! Day Two | Some lift -> 3xAny").unwrap()
             )
         }
 
+        #[test]
+        fn parses_starting_weights() {
+            let new_program = NewProgram::parse(
+                "Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r | Leg press -> 2x15,1x15-25 @ add30 !!! Leg press = 90",
+            )
+            .unwrap();
+            assert_eq!(
+                new_program.weights,
+                HashMap::from([(LiftId::new("Leg press"), 90.0)])
+            );
+        }
+
+        #[test]
+        fn parses_over_multiple_lines() {
+            assert_eq!(
+                NewProgram::parse("Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r !! Core | Plank -> 1x30s").unwrap(),
+                NewProgram::parse("Legs !!! 135 !!!\n    Legs | Squat -> 3x5 @ 1r !!\n    Core | Plank -> 1x30s\n").unwrap()
+            );
+        }
+
+        #[test]
+        fn parses_named_references() {
+            let new_program = NewProgram::parse(
+                "Legs !!! 100, squat = 1.35r, deadlift = 185 !!! Legs | Squat -> 3x5 @ 1r[squat] | Deadlift -> 1x5 @ 1r[deadlift] | Leg press -> 3x10 @ 2r[leg press]",
+            )
+            .unwrap();
+            assert_eq!(new_program.starting_reference_weight, 100.0);
+            assert_eq!(
+                new_program.starting_reference_weights,
+                BTreeMap::from([
+                    (
+                        "squat".to_string(),
+                        StartingReference::RelativeToReference {
+                            multiplier: 1.35,
+                            offset: 0.0
+                        }
+                    ),
+                    ("deadlift".to_string(), StartingReference::Fixed(185.0)),
+                ])
+            );
+            let program = new_program.start();
+            assert_eq!(
+                program.reference_weights,
+                BTreeMap::from([
+                    ("squat".to_string(), 135.0),
+                    ("deadlift".to_string(), 185.0),
+                    ("leg press".to_string(), 100.0),
+                ])
+            );
+            assert_eq!(program.starting_reference_weights, program.reference_weights);
+        }
+
+        #[test]
+        fn parses_settings() {
+            let new_program = NewProgram::parse(
+                "Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r !!!  !!! progression = linear+10",
+            )
+            .unwrap();
+            assert_eq!(
+                new_program.settings.progression,
+                Some(ProgressionScheme::Linear {
+                    increment: Some(10.0)
+                })
+            );
+            NewProgram::parse("Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r !!!  !!! colour = blue")
+                .expect_err("Should have failed to parse");
+        }
+
+        #[test]
+        fn fails_to_parse_starting_weight_of_unknown_lift() {
+            NewProgram::parse("Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r !!! Deadlift = 90")
+                .expect_err("Should have failed to parse");
+        }
+
+        #[test]
+        fn starts() {
+            let program = NewProgram::parse(
+                "Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r | Leg press -> 2x15,1x15-25 @ add30 !!! Leg press = 90",
+            )
+            .unwrap()
+            .start();
+            assert_eq!(program.name, "Legs");
+            assert_eq!(program.reference_weight, 135.0);
+            assert_eq!(program.starting_reference_weight, 135.0);
+            assert_eq!(program.workouts_completed, 0);
+            assert_eq!(
+                program.next_workout()[1].to_string(),
+                "Leg press -> 2x15,1x15-25 @ 90 lb"
+            );
+        }
+
         #[test]
         fn fails_to_parse() {
             NewProgram::parse("Banana !!! Banana").expect_err("Should have failed to parse");
         }
+
+        #[test]
+        fn round_trips_through_notation() {
+            let notation = "\"Push !!! Pull\" !!! 135, \"front, squat\" = 1.35r !!! \"A | B\" | \"Curl = press\" -> 3x10 @ add5 | Squat -> 3x5 @ 1r[\"front, squat\"] !! B | Curl -> 3x10 @ add5 !!! \"Curl = press\" = 20 !! Curl = 30 !!! unit = kg";
+            let program = NewProgram::parse(notation).unwrap();
+            assert_eq!(program.name, "Push !!! Pull");
+            assert_eq!(program.days[0].lifts[0].name, "Curl = press");
+            assert_eq!(program.to_string(), notation);
+            let notation = "\"Push\tPull\" !!! 135 !!!\n\"A\nB\" | \"Curl\tpress\" -> 3x10 @ 1r";
+            let program = NewProgram::parse(notation).unwrap();
+            assert_eq!(program.days[0].name, "A\nB");
+            assert_eq!(NewProgram::parse(&program.to_string()).unwrap(), program);
+            assert_eq!(NewProgram::parse(&program.formatted()).unwrap(), program);
+            templates::all().iter().for_each(|template| {
+                let program = template.new_program();
+                assert_eq!(NewProgram::parse(&program.to_string()).unwrap(), program);
+            });
+            assert_eq!(
+                NewProgram::parse("Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r").unwrap().to_string(),
+                "Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r"
+            );
+        }
+
+        #[test]
+        fn lints_what_is_probably_a_mistake() {
+            let warnings = NewProgram::lint(
+                "Legs !!! 135, squat = 1.35r, bench = 1r !!!
+                Legs | Squat -> 3x5 @ 1r[squat] | Leg curl -> 3x15-10 @ add5 !!
+                Core | Plank -> 1x30s | Ab rollout -> 3xAny
+                !!! Leg curl = 30 !! Squat = 90",
+            )
+            .unwrap_err()
+            .downcast::<Diagnostics>()
+            .unwrap()
+            .0;
+            let messages: Vec<(&str, usize, Option<&str>)> = warnings
+                .iter()
+                .map(|it| (it.message.as_str(), it.line, it.suggestion.as_deref()))
+                .collect();
+            assert_eq!(
+                messages,
+                vec![
+                    ("A rep range must go from fewer reps to more: 15-10", 2, Some("10-15")),
+                    ("Nothing in this day progresses: Core", 3, None),
+                    ("No lift is based on this reference weight: bench", 1, None),
+                    ("This lift doesn't add to its previous weight, so never uses a starting weight: Squat", 4, None),
+                ]
+            );
+            // Weights the plates, or the way they're rounded, can't make.
+            let warnings_of = |notation: &str| -> Vec<String> {
+                NewProgram::lint(notation)
+                    .map_or_else(|error| error.downcast::<Diagnostics>().unwrap().0, |_| Vec::new())
+                    .into_iter()
+                    .map(|it| it.message)
+                    .collect()
+            };
+            assert_eq!(
+                warnings_of("Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r round 2.5"),
+                vec!["This lift comes to 137.5 lb, which the bar can't be loaded to with the plates there are (the nearest is 135 lb): 1r"]
+            );
+            assert_eq!(
+                warnings_of("Legs !!! 135 !!! Legs | Row -> 3x5 @ add5 !!! Row = 135 !!! plates = 45 25 10 5"),
+                vec!["This lift comes to 140 lb, which the bar can't be loaded to with the plates there are (the nearest is 135 lb): add5"]
+            );
+            assert!(warnings_of("Legs !!! 135 !!! Legs | Row -> 3x5 @ add10 !!! Row = 135 !!! plates = 45 25 10 5").is_empty());
+            NewProgram::lint("Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r !!! Squat = 9x")
+                .unwrap_err()
+                .downcast::<Diagnostics>()
+                .unwrap();
+            templates::all()
+                .iter()
+                .filter(|template| !template.name.starts_with("gzcl-4day"))
+                .for_each(|template| NewProgram::lint(template.notation).unwrap());
+            // Their core days are done without progressing.
+            ["gzcl-4day", "gzcl-4day-stages"].iter().for_each(|name| {
+                let warnings = NewProgram::lint(templates::find(name).unwrap().notation)
+                    .unwrap_err()
+                    .downcast::<Diagnostics>()
+                    .unwrap()
+                    .0;
+                assert_eq!(warnings.len(), 1);
+                assert_eq!(warnings[0].message, "Nothing in this day progresses: Core");
+            });
+        }
+
+        #[test]
+        fn formats_one_day_to_a_line() {
+            let program = NewProgram::parse(
+                "Legs !!! 135 !!! A | Squat -> 3x5,3x5 @ 1r !! B | Plank -> 1x30s progress time+5 !!!  !!! unit = kg",
+            )
+            .unwrap();
+            assert_eq!(
+                program.formatted(),
+                "Legs !!! 135 !!!\n    A | Squat -> 6x5 @ 1r !!\n    B | Plank -> 1x30s progress time+5\n!!!  !!! unit = kg\n"
+            );
+            assert_eq!(NewProgram::parse(&program.formatted()).unwrap(), program);
+            templates::all().iter().for_each(|template| {
+                let program = template.new_program();
+                assert_eq!(NewProgram::parse(&program.formatted()).unwrap(), program);
+            });
+        }
+
+        #[test]
+        fn points_at_what_failed_to_parse() {
+            let problems = |notation: &str| {
+                NewProgram::parse(notation)
+                    .unwrap_err()
+                    .downcast::<Diagnostics>()
+                    .unwrap()
+                    .0
+            };
+            let diagnostics = problems(
+                "Legs !!! 135 !!!\nLegs | Squat -> 3x5 @ 1r | Calf raise -> 3x15 @ ad20 !!\nCore | Plank -> 1x30s",
+            );
+            assert_eq!((diagnostics[0].line, diagnostics[0].column, diagnostics[0].width), (2, 49, 4));
+            assert_eq!(diagnostics[0].suggestion.as_deref(), Some("add20"));
+            let diagnostics = problems("Legs !!! 135 !!! Legs | Squat -> 3x5 @ 1r progres linear");
+            assert_eq!(diagnostics[0].line, 1);
+            assert_eq!(diagnostics[0].suggestion.as_deref(), Some("progress"));
+            // The same text in an earlier lift isn't mistaken for where the problem is.
+            let diagnostics = problems("Legs !!! 100 !!! Legs | Squat -> 3x5 @ 1r | Squat -> 3x8 @ 0.8r");
+            assert_eq!((diagnostics[0].column, diagnostics[0].width), (45, 5));
+            let diagnostics = problems("Legs !!! 100 !!! Legs | Squat -> 3x5 @ 1r | Row -> 3x5 @ 1r | Row -> 3x8 @ 1r");
+            assert_eq!((diagnostics[0].column, diagnostics[0].width), (63, 3));
+            let warnings = NewProgram::lint(
+                "Legs !!! 100 !!! Legs | Curl -> 3x10-8 @ add5 | Leg curl -> 3x10-8 @ add5 !!! Curl = 20 !! Leg curl = 30",
+            )
+            .unwrap_err()
+            .downcast::<Diagnostics>()
+            .unwrap()
+            .0;
+            assert_eq!(
+                warnings.iter().map(|it| it.column).collect::<Vec<usize>>(),
+                vec![35, 63]
+            );
+        }
+
+        #[test]
+        fn reports_every_problem_at_once() {
+            let diagnostics = NewProgram::parse(
+                "Legs !!! 135 !!!
+                Legs | Squat -> 3x5 @ 1r | Leg press -> 3x10 @ ad20 !!
+                Push | Bench press -> 3x5 @ 1r | Bench press -> 3x8 @ 0.8r !!
+                Pull | Row -> 3x10 @ add5 | Good morning -> 3x10 @ 0.2r-40 | Pullup -> 3x5 @ 0.2r-40 equipment bodyweight !!
+                Core |
+                !!! Curl = 20",
+            )
+            .unwrap_err()
+            .downcast::<Diagnostics>()
+            .unwrap()
+            .0;
+            let messages: Vec<(&str, usize)> = diagnostics
+                .iter()
+                .map(|it| (it.message.as_str(), it.line))
+                .collect();
+            assert_eq!(
+                messages,
+                vec![
+                    ("Cannot parse weight: ad20", 2),
+                    ("A day can't have two lifts with the same name: Bench press", 3),
+                    ("A lift that adds to its previous weight needs a starting weight, like 'Row = 45' after the days: Row", 4),
+                    ("The weight starts out below zero, at -10: 0.2r-40", 4),
+                    ("A day needs lifts: Core |", 5),
+                ]
+            );
+            // Nothing is said about weights based on references that didn't parse.
+            let diagnostics = NewProgram::parse("Legs !!! abc !!! Legs | Squat -> 3x5 @ 0.5r-10")
+                .unwrap_err()
+                .downcast::<Diagnostics>()
+                .unwrap()
+                .0;
+            assert_eq!(diagnostics.len(), 1);
+        }
     }
 
     mod program {
         use super::*;
 
+        fn start_gzcl_4day_stages(reference_weight: f64) -> Program {
+            let mut new_program = templates::find("gzcl-4day-stages").unwrap().new_program();
+            new_program.starting_reference_weight = reference_weight;
+            new_program.start()
+        }
+
         #[test]
         fn stores_weights() {
             assert_eq!(
-                format!("{}", start_gzcl_4day(100).next_workout()[0]),
-                "Weighted Pullup -> 4x3,1x3+ @ 20"
+                format!("{}", start_gzcl_4day(100.0).next_workout()[0]),
+                "Weighted Pullup -> 4x3,1x3+ @ bw+20 lb"
             );
             assert_eq!(
-                format!("{}", start_gzcl_4day(100).next_workout()[1]),
+                format!("{}", start_gzcl_4day(100.0).next_workout()[1]),
                 "Pullup -> 3x7+"
             );
         }
 
+        #[test]
+        fn assists_bodyweight_lifts_that_go_below_zero() {
+            let program = start_gzcl_4day_stages(45.0);
+            assert_eq!(program.reference_weights["pullup"], -7.0);
+            let attempts = program.next_workout();
+            assert_eq!(attempts[0].prescribed_weight(), Some(-5.0));
+            assert_eq!(
+                attempts[0].to_string(),
+                "Weighted Pullup -> 4x3,1x3+ @ bw-5 lb (stage 1 of 3)"
+            );
+            assert_eq!(attempts[0].plates, None);
+            assert_eq!(attempts[0].effective_load(), None);
+            let attempts = program.log_bodyweight(180.0).next_workout();
+            assert_eq!(attempts[0].effective_load(), Some(175.0));
+            assert_eq!(attempts[1].effective_load(), Some(180.0));
+            assert_eq!(attempts[2].effective_load(), None);
+        }
+
+        #[test]
+        fn assists_only_from_references_of_bodyweight_lifts() {
+            let program = NewProgram::parse(
+                "Pull !!! 45, pullup = 0.5r-30, row = 0.5r-30 !!! A | Weighted Pullup -> 3x5 @ 1r[pullup] equipment bodyweight | Barbell Row -> 3x5 @ 1r[row]",
+            )
+            .unwrap()
+            .start();
+            assert_eq!(program.reference_weights["pullup"], -7.0);
+            assert_eq!(program.reference_weights["row"], 0.0);
+        }
+
+        #[test]
+        fn keeps_the_weight_added_to_bodyweight_as_written() {
+            let program = NewProgram::parse("Push !!! 100 !!! A | Dip -> 3x8+ @ bw+25 progress reps")
+                .unwrap()
+                .start();
+            let after = program
+                .complete_workout(&[LiftAttemptResult::performed(vec![8, 8, 10], Some(25.0), None)]);
+            assert_eq!(after.next_workout()[0].to_string(), "Dip -> 3x9+ @ bw+25 lb");
+        }
+
+        #[test]
+        fn loads_bodyweight_schemes() {
+            let program = NewProgram::parse(
+                "Calisthenics !!! 100 !!! A | Dip -> 3x8 @ bw+25 | Assisted pullup -> 3x8 @ bw-40 | Pushup -> 3x15 @ bw",
+            )
+            .unwrap()
+            .start()
+            .log_bodyweight(200.0);
+            assert_eq!(
+                program
+                    .next_workout()
+                    .iter()
+                    .map(|it| (it.to_string(), it.effective_load(), it.plates.is_some()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("Dip -> 3x8 @ bw+25 lb".to_string(), Some(225.0), false),
+                    ("Assisted pullup -> 3x8 @ bw-40 lb".to_string(), Some(160.0), false),
+                    ("Pushup -> 3x15 @ bw".to_string(), Some(200.0), false),
+                ]
+            );
+            assert_eq!(program.convert_units(Unit::Kg).bodyweight, Some(90.0));
+        }
+
         #[test]
         fn all_non_reference_weights_initialized() {
             assert_eq!(
-                format!("{}", start_gzcl_4day(100).next_workout()[3]),
-                "Face Pull -> 2x15,1x15-25 @ 30"
+                format!("{}", start_gzcl_4day(100.0).next_workout()[3]),
+                "Face Pull -> 2x15,1x15-25 @ 30 lb"
             );
             assert_eq!(
-                format!("{}", start_gzcl_4day(100).next_workout()[4]),
-                "Cable Curl -> 2x15,1x15-25 @ 20"
+                format!("{}", start_gzcl_4day(100.0).next_workout()[4]),
+                "Cable Curl -> 2x15,1x15-25 @ 20 lb"
             );
         }
 
@@ -297,19 +1754,19 @@ mod tests {
 
             #[test]
             fn increments_weights() {
-                let before = start_gzcl_4day(100);
+                let before = start_gzcl_4day(100.0);
                 let lift_incremented = before.days[0].lifts[3].clone();
                 assert_eq!(
                     lift_incremented.to_string(),
-                    "Face Pull -> 2x15,1x15-25 @ add20"
+                    "Face Pull -> 2x15,1x15-25 @ add20 equipment machine"
                 );
                 let lift_not_incremented = before.days[0].lifts[4].clone();
                 assert_eq!(
                     lift_not_incremented.to_string(),
-                    "Cable Curl -> 2x15,1x15-25 @ add20"
+                    "Cable Curl -> 2x15,1x15-25 @ add20 equipment machine"
                 );
-                assert_eq!(before.weights[&lift_incremented], 30);
-                assert_eq!(before.weights[&lift_not_incremented], 20);
+                assert_eq!(before.weights[&lift_incremented.id()], 30.0);
+                assert_eq!(before.weights[&lift_not_incremented.id()], 20.0);
                 let after = before.complete_workout(&[
                     Completed {
                         completed_maximum_reps: true,
@@ -327,16 +1784,79 @@ mod tests {
                         completed_maximum_reps: false,
                     },
                 ]);
-                assert_eq!(after.weights[&lift_incremented], 50);
-                assert_eq!(after.weights[&lift_not_incremented], 20);
+                assert_eq!(after.weights[&lift_incremented.id()], 50.0);
+                assert_eq!(after.weights[&lift_not_incremented.id()], 20.0);
+            }
+
+            #[test]
+            fn increments_weights_from_performed_reps() {
+                let before = start_gzcl_4day(100.0);
+                let face_pull = before.days[0].lifts[3].clone();
+                let cable_curl = before.days[0].lifts[4].clone();
+                let performed = |reps: Vec<usize>, weight: Option<f64>| {
+                    LiftAttemptResult::performed(reps, weight, None)
+                };
+                let after = before.complete_workout(&[
+                    performed(vec![3, 3, 3, 3, 5], None),
+                    performed(vec![7, 7, 7], None),
+                    performed(vec![10, 10, 10], None),
+                    performed(vec![15, 15, 25], Some(35.0)),
+                    performed(vec![15, 15, 24], Some(25.0)),
+                ]);
+                assert_eq!(after.weights[&face_pull.id()], 55.0);
+                assert_eq!(after.weights[&cable_curl.id()], 25.0);
+            }
+
+            #[test]
+            fn keeps_prescribed_weights_of_failed_lifts() {
+                let before = start_gzcl_4day(100.0);
+                let face_pull = before.days[0].lifts[3].clone();
+                let performed = |reps: Vec<usize>, weight: Option<f64>| {
+                    LiftAttemptResult::performed(reps, weight, None)
+                };
+                let after = before.complete_workout(&[
+                    performed(vec![3, 3, 3, 3, 5], None),
+                    performed(vec![7, 7, 7], None),
+                    performed(vec![10, 10, 10], None),
+                    performed(vec![15, 10, 8], Some(45.0)),
+                    performed(vec![15, 15, 24], Some(20.0)),
+                ]);
+                assert_eq!(after.weights[&face_pull.id()], 30.0);
+            }
+
+            #[test]
+            fn keeps_weights_when_sets_change() {
+                let mut program = start_gzcl_4day(100.0);
+                program.days[0].lifts[3] =
+                    Lift::parse("Face pull -> 3x12 @ add20 equipment machine").unwrap();
+                assert_eq!(program.next_workout()[3].weight, Some(30.0));
+            }
+
+            #[test]
+            fn keeps_weights_of_same_named_lifts_with_different_sets_apart() {
+                let completed = [Completed {
+                    completed_maximum_reps: true,
+                }];
+                let program = NewProgram::parse(
+                    "Legs !!! 135 !!! Heavy | Squat -> 5x5 @ add10 !! Light | Squat -> 3x8 @ add5 !!! Squat = 100",
+                )
+                .unwrap()
+                .start();
+                assert_eq!(program.next_workout()[0].weight, Some(100.0));
+                let program = program.complete_workout(&completed);
+                assert_eq!(program.next_workout()[0].weight, Some(100.0));
+                let program = program.complete_workout(&completed);
+                assert_eq!(program.next_workout()[0].weight, Some(110.0));
+                let program = program.complete_workout(&completed);
+                assert_eq!(program.next_workout()[0].weight, Some(105.0));
             }
 
             #[test]
             fn doesnt_increment_reference_weight_if_any_not_completed() {
-                let completed_all = [Completed {
+                let completed_all = vec![Completed {
                     completed_maximum_reps: true,
                 }; 5];
-                let one_incomplete = [
+                let one_incomplete = vec![
                     NotCompleted,
                     Completed {
                         completed_maximum_reps: true,
@@ -351,45 +1871,45 @@ mod tests {
                         completed_maximum_reps: true,
                     },
                 ];
-                assert_eq!(start_gzcl_4day(100).reference_weight, 100);
+                assert_eq!(start_gzcl_4day(100.0).reference_weight, 100.0);
                 assert_eq!(
-                    start_gzcl_4day(100)
+                    start_gzcl_4day(100.0)
                         .complete_workout(&one_incomplete)
                         .complete_workout(&completed_all)
                         .complete_workout(&completed_all)
                         .complete_workout(&completed_all)
                         .complete_workout(&completed_all)
                         .reference_weight,
-                    100
+                    100.0
                 );
             }
             #[test]
             fn increments_reference_weight_if_all_completed() {
-                assert_eq!(start_gzcl_4day(100).reference_weight, 100);
-                assert_eq!(start_gzcl_4day(100).starting_reference_weight, 100);
-                let all_lifts_completed = [Completed {
+                assert_eq!(start_gzcl_4day(100.0).reference_weight, 100.0);
+                assert_eq!(start_gzcl_4day(100.0).starting_reference_weight, 100.0);
+                let all_lifts_completed = vec![Completed {
                     completed_maximum_reps: true,
                 }; 5];
 
-                let before_cycle_completed = start_gzcl_4day(100)
+                let before_cycle_completed = start_gzcl_4day(100.0)
                     .complete_workout(&all_lifts_completed)
                     .complete_workout(&all_lifts_completed)
                     .complete_workout(&all_lifts_completed);
-                assert_eq!(before_cycle_completed.reference_weight, 100);
+                assert_eq!(before_cycle_completed.reference_weight, 100.0);
 
                 let after_cycle_completed =
                     before_cycle_completed.complete_workout(&all_lifts_completed);
-                assert_eq!(after_cycle_completed.reference_weight, 105);
-                assert_eq!(after_cycle_completed.starting_reference_weight, 100);
+                assert_eq!(after_cycle_completed.reference_weight, 105.0);
+                assert_eq!(after_cycle_completed.starting_reference_weight, 100.0);
             }
             #[test]
             fn is_not_affected_by_failures_in_previous_cycles() {
-                let incremented_weight = 105;
-                let not_incremented_weight = 100;
-                let all_lifts_completed = [Completed {
+                let incremented_weight = 105.0;
+                let not_incremented_weight = 100.0;
+                let all_lifts_completed = vec![Completed {
                     completed_maximum_reps: true,
                 }; 5];
-                let all_lifts_not_completed = [NotCompleted; 5];
+                let all_lifts_not_completed = vec![NotCompleted; 5];
 
                 assert_eq!(
                     start_gzcl_4day(not_incremented_weight).reference_weight,
@@ -425,21 +1945,379 @@ mod tests {
                     incremented_weight
                 );
             }
+            #[test]
+            fn increments_named_references_independently() {
+                let program = NewProgram::parse(
+                    "Split !!! 100, squat = 150, bench = 100 !!! A | Squat -> 3x5 @ 1r[squat] | Row -> 3x5 @ 0.5r !! B | Bench press -> 3x5 @ 1r[bench] | Squat -> 1x10 @ 0.5r[squat]",
+                )
+                .unwrap()
+                .start();
+                let max_reps = Completed {
+                    completed_maximum_reps: true,
+                };
+                let after_cycle = program
+                    .complete_workout(&[max_reps.clone(), max_reps.clone()])
+                    .complete_workout(&[max_reps, NotCompleted]);
+                assert_eq!(after_cycle.reference_weights["squat"], 150.0);
+                assert_eq!(after_cycle.reference_weights["bench"], 105.0);
+                assert_eq!(after_cycle.reference_weight, 105.0);
+                assert_eq!(after_cycle.starting_reference_weights["bench"], 100.0);
+                assert_eq!(
+                    after_cycle.next_workout()[0].to_string(),
+                    "Squat -> 3x5 @ 150 lb"
+                );
+            }
+
+            #[test]
+            fn follows_the_programs_progression() {
+                let program = NewProgram::parse(
+                    "Legs !!! 100 !!! Legs | Squat -> 3x5-8 @ 1r | Leg press -> 3x10-12 @ add20 !!! Leg press = 90 !!! progression = linear",
+                )
+                .unwrap()
+                .start();
+                let completed = Completed {
+                    completed_maximum_reps: false,
+                };
+                let after = program.complete_workout(&[completed.clone(), completed]);
+                assert_eq!(after.reference_weight, 105.0);
+                assert_eq!(after.next_workout()[1].to_string(), "Leg press -> 3x10-12 @ 110 lb");
+            }
+
+            #[test]
+            fn lifts_can_choose_their_own_progression() {
+                let program = NewProgram::parse(
+                    "Legs !!! 100 !!! Legs | Squat -> 3x5+ @ 1r progress 531+10 | Leg press -> 3x10-12 @ add20 !!! Leg press = 90 !!! progression = linear",
+                )
+                .unwrap()
+                .start();
+                assert_eq!(program.next_workout()[0].to_string(), "Squat -> 2x5,1x5+ @ 85 lb");
+                let completed = Completed {
+                    completed_maximum_reps: false,
+                };
+                let after_first_week = program.complete_workout(&[completed.clone(), completed.clone()]);
+                assert_eq!(after_first_week.reference_weight, 100.0);
+                assert_eq!(
+                    after_first_week.next_workout()[0].to_string(),
+                    "Squat -> 2x3,1x3+ @ 90 lb"
+                );
+                assert_eq!(
+                    after_first_week.next_workout()[1].to_string(),
+                    "Leg press -> 3x10-12 @ 110 lb"
+                );
+                let after_deload = (0..3).fold(after_first_week, |program, _| {
+                    program.complete_workout(&[completed.clone(), completed.clone()])
+                });
+                assert_eq!(after_deload.reference_weight, 110.0);
+                assert_eq!(after_deload.next_workout()[0].to_string(), "Squat -> 2x5,1x5+ @ 95 lb");
+            }
+
+            #[test]
+            fn moves_failed_t1_lifts_through_stages() {
+                let completed = vec![Completed {
+                    completed_maximum_reps: false,
+                }; 5];
+                let failed_t1 = [vec![NotCompleted], vec![completed[0].clone(); 4]].concat();
+                let complete_cycle = |program: Program, first_day: &[LiftAttemptResult]| {
+                    program
+                        .complete_workout(first_day)
+                        .complete_workout(&completed)
+                        .complete_workout(&completed)
+                        .complete_workout(&completed)
+                };
+                let after_one_failure = complete_cycle(start_gzcl_4day_stages(200.0), &failed_t1);
+                assert_eq!(after_one_failure.reference_weights["pullup"], 70.0);
+                assert_eq!(
+                    after_one_failure.next_workout()[0].to_string(),
+                    "Weighted Pullup -> 5x2,1x2+ @ bw+70 lb (stage 2 of 3)"
+                );
+                assert_eq!(
+                    after_one_failure.next_workout()[0].lift,
+                    Lift::parse("Weighted Pullup -> 5x2,1x2+ @ 1r[pullup] progress gzclp-t1 equipment bodyweight").unwrap()
+                );
+                let after_two_failures = complete_cycle(after_one_failure, &failed_t1);
+                assert_eq!(
+                    after_two_failures.next_workout()[0].to_string(),
+                    "Weighted Pullup -> 9x1,1x1+ @ bw+70 lb (stage 3 of 3)"
+                );
+                let after_completing_stage_3 = complete_cycle(after_two_failures.clone(), &completed);
+                assert_eq!(
+                    after_completing_stage_3.next_workout()[0].to_string(),
+                    "Weighted Pullup -> 9x1,1x1+ @ bw+75 lb (stage 3 of 3)"
+                );
+                let after_three_failures = complete_cycle(after_two_failures, &failed_t1);
+                assert_eq!(
+                    after_three_failures.next_workout()[0].to_string(),
+                    "Weighted Pullup -> 4x3,1x3+ @ bw+60 lb (stage 1 of 3)"
+                );
+            }
+
+            #[test]
+            fn rounds_the_way_the_program_and_lifts_say() {
+                let program = NewProgram::parse(
+                    "Legs !!! 101 !!! Legs | Squat -> 3x5 @ 1r | Leg press -> 3x10 @ add10 round 5 down !!! Leg press = 93 !!! deload = 10% after 1 !! rounding = 2.5 nearest",
+                )
+                .unwrap()
+                .start();
+                assert_eq!(program.next_workout()[0].to_string(), "Squat -> 3x5 @ 100 lb");
+                assert_eq!(program.next_workout()[1].to_string(), "Leg press -> 3x10 @ 90 lb");
+                let (_, deloads) = program.complete_workout_with_deloads(&[NotCompleted, NotCompleted]);
+                assert_eq!(deloads[0].to, 80.0);
+                assert_eq!(deloads[1].to, 90.0);
+            }
+
+            #[test]
+            fn deloads_after_stalling() {
+                let program = NewProgram::parse(
+                    "Legs !!! 200 !!! Legs | Squat -> 3x5 @ 1r | Leg press -> 3x10-12 @ add20 !!! Leg press = 93 !!! deload = 10% after 3",
+                )
+                .unwrap()
+                .start();
+                let failed = [NotCompleted, NotCompleted];
+                let after_two_stalls = program
+                    .complete_workout(&failed)
+                    .complete_workout(&failed);
+                assert_eq!(after_two_stalls.reference_progressions[&None].stalls, 2);
+                let (after_three_stalls, deloads) =
+                    after_two_stalls.complete_workout_with_deloads(&failed);
+                assert_eq!(after_three_stalls.reference_weight, 180.0);
+                assert_eq!(after_three_stalls.reference_progressions[&None].stalls, 0);
+                assert_eq!(
+                    deloads,
+                    vec![
+                        Deload {
+                            what: "Leg press".to_string(),
+                            from: 93.0,
+                            to: 80.0,
+                            unit: Unit::Lb,
+                            stalls: 3
+                        },
+                        Deload {
+                            what: "reference weight".to_string(),
+                            from: 200.0,
+                            to: 180.0,
+                            unit: Unit::Lb,
+                            stalls: 3
+                        }
+                    ]
+                );
+            }
+
+            #[test]
+            fn only_deloads_when_the_program_says() {
+                let program = NewProgram::parse(
+                    "Legs !!! 200 !!! Legs | Squat -> 3x5 @ 1r | Leg press -> 3x10-12 @ add20 !!! Leg press = 93",
+                )
+                .unwrap()
+                .start();
+                let failed = [NotCompleted, NotCompleted];
+                let (after_five_stalls, deloads) = (0..4)
+                    .fold(program, |program, _| program.complete_workout(&failed))
+                    .complete_workout_with_deloads(&failed);
+                assert_eq!(deloads, vec![]);
+                assert_eq!(after_five_stalls.reference_weight, 200.0);
+                assert_eq!(after_five_stalls.weights[&LiftId::new("Leg press")], 93.0);
+                assert_eq!(after_five_stalls.reference_progressions[&None].stalls, 5);
+            }
+
+            #[test]
+            fn progressing_resets_stalls() {
+                let program = NewProgram::parse(
+                    "Legs !!! 200 !!! Legs | Squat -> 3x5 @ 1r !!!  !!! deload = 20% after 2",
+                )
+                .unwrap()
+                .start();
+                let max_reps = Completed {
+                    completed_maximum_reps: true,
+                };
+                let after = program
+                    .complete_workout(&[NotCompleted])
+                    .complete_workout(&[max_reps])
+                    .complete_workout(&[NotCompleted]);
+                assert_eq!(after.reference_weight, 205.0);
+                let (after, deloads) = after.complete_workout_with_deloads(&[NotCompleted]);
+                assert_eq!(after.reference_weight, 160.0);
+                assert_eq!(deloads.len(), 1);
+                assert_eq!(
+                    deloads[0].to_string(),
+                    "Deload: reference weight stalled 2 times in a row, dropping it from 205 lb to 160 lb"
+                );
+            }
+
+            #[test]
+            fn never_deloads_if_told_not_to() {
+                let program = NewProgram::parse(
+                    "Legs !!! 200 !!! Legs | Squat -> 3x5 @ 1r !!!  !!! deload = never",
+                )
+                .unwrap()
+                .start();
+                let after = (0..5).fold(program, |program, _| program.complete_workout(&[NotCompleted]));
+                assert_eq!(after.reference_weight, 200.0);
+                assert_eq!(after.reference_progressions[&None].stalls, 5);
+            }
+
+            #[test]
+            fn progresses_off_the_top_sets() {
+                let program = NewProgram::parse(
+                    "Legs !!! 200, squat = 300 !!! Legs | Squat -> 1x5+ @ 1r[squat], 3x8 @ 0.7r[squat], 2x10 @ 0.5r progress 531",
+                )
+                .unwrap()
+                .start();
+                assert_eq!(
+                    program.next_workout()[0].to_string(),
+                    "Squat -> 2x5,1x5+ @ 255 lb, 3x8 @ 180 lb, 2x10 @ 100 lb"
+                );
+                let missed_back_off =
+                    LiftAttemptResult::performed(vec![5, 5, 7, 8, 8, 2, 10, 10], None, None);
+                let after = program.complete_workout(&[missed_back_off]);
+                assert_eq!(
+                    after.next_workout()[0].to_string(),
+                    "Squat -> 2x3,1x3+ @ 270 lb, 3x8 @ 190 lb, 2x10 @ 100 lb"
+                );
+            }
+
+            #[test]
+            fn autoregulates_from_effort_targets() {
+                let program = NewProgram::parse(
+                    "Legs !!! 200 !!! Legs | Squat -> 3x5 @ RPE8 @ 1r progress rpe | Leg press -> 3x10 @ 2RIR @ add20 progress rpe !!! Leg press = 300",
+                )
+                .unwrap()
+                .start();
+                let felt = |rpe: f64| LiftAttemptResult::performed(vec![5, 5, 5], None, Some(rpe));
+                let after = program.complete_workout(&[felt(9.0), felt(6.0)]);
+                assert_eq!(after.reference_weight, 195.0);
+                assert_eq!(after.next_workout()[1].to_string(), "Leg press -> 3x10 @ 2RIR @ 315 lb");
+            }
+
+            #[test]
+            fn progresses_each_lift_in_a_superset() {
+                let program = NewProgram::parse(
+                    "Pull !!! 100 !!! Pull | [Face Pull -> 3x15 @ add5 + Cable Curl -> 3x12 @ add5] !!! Face Pull = 30 !! Cable Curl = 20",
+                )
+                .unwrap()
+                .start();
+                let attempts = program.next_workout();
+                assert_eq!(
+                    attempts.iter().map(|it| it.superset).collect::<Vec<_>>(),
+                    vec![Some(0), Some(0)]
+                );
+                let after = program.complete_workout(&[
+                    LiftAttemptResult::performed(vec![15, 15, 15], None, None),
+                    LiftAttemptResult::performed(vec![12, 10, 8], None, None),
+                ]);
+                assert_eq!(
+                    after.next_workout().iter().map(|it| it.to_string()).collect::<Vec<_>>(),
+                    vec!["Face Pull -> 3x15 @ 35 lb", "Cable Curl -> 3x12 @ 20 lb"]
+                );
+            }
+
+            #[test]
+            fn progresses_reps_and_seconds_of_weightless_lifts() {
+                let program = NewProgram::parse(
+                    "Core !!! 100 !!! A | Plank -> 1x30s @ any progress time+10 | Pullup -> 2x5,1x5+ equipment bodyweight progress reps | Knee raises -> 3xAny",
+                )
+                .unwrap()
+                .start();
+                let results = |reps: Vec<usize>| {
+                    [
+                        LiftAttemptResult::performed(vec![60], None, None),
+                        LiftAttemptResult::performed(reps, None, None),
+                        Completed { completed_maximum_reps: true },
+                    ]
+                };
+                let after = program
+                    .complete_workout(&results(vec![5, 5, 9]))
+                    .complete_workout(&results(vec![6, 5, 7]));
+                assert_eq!(
+                    after.next_workout().iter().map(|it| it.to_string()).collect::<Vec<_>>(),
+                    vec!["Plank -> 1x50s @ any", "Pullup -> 2x6,1x6+", "Knee raises -> 3xAny"]
+                );
+            }
+
+            #[test]
+            fn graduates_to_the_weighted_variant() {
+                let program = NewProgram::parse(
+                    "Pull !!! 100 !!! A | Pullup -> 3x10+ progress reps equipment bodyweight graduate at 12 to Weighted Pullup @ add5 from 10 | Plank -> 1x30s",
+                )
+                .unwrap()
+                .start();
+                let pullups = |reps: Vec<usize>| {
+                    [LiftAttemptResult::performed(reps, None, None), NotCompleted]
+                };
+                let after = program
+                    .complete_workout(&pullups(vec![11, 11, 12]))
+                    .complete_workout(&pullups(vec![12, 12, 11]));
+                assert_eq!(after.next_workout()[0].to_string(), "Pullup -> 3x12+");
+                let graduated = after.complete_workout(&pullups(vec![12, 12, 14]));
+                assert_eq!(
+                    graduated.next_workout()[0].to_string(),
+                    "Weighted Pullup -> 3x10+ @ bw+10 lb"
+                );
+                assert_eq!(graduated.days[0].lifts[0].equipment, Some(Equipment::Bodyweight));
+                assert!(graduated.lift_progressions.is_empty());
+            }
+
+            #[test]
+            fn warms_up_only_when_asked() {
+                let warmups = |notation: &str| -> Vec<Vec<f64>> {
+                    NewProgram::parse(notation)
+                        .unwrap()
+                        .start()
+                        .next_workout()
+                        .iter()
+                        .map(|attempt| attempt.warmups.iter().map(|set| set.weight).collect())
+                        .collect()
+                };
+                assert_eq!(
+                    warmups("Legs !!! 315 !!! Legs | Squat -> 3x5 @ 1r | Leg press -> 3x10 @ add20 !!! Leg press = 200"),
+                    vec![Vec::<f64>::new(), vec![]]
+                );
+                assert_eq!(
+                    warmups("Legs !!! 315 !!! Legs | Squat -> 3x5 @ 1r | Leg press -> 3x10 @ add20 !!! Leg press = 200 !!! warmup = bar x10, 40% x5, 60% x3, 80% x2"),
+                    vec![vec![45.0, 125.0, 185.0, 250.0], vec![]]
+                );
+                assert_eq!(
+                    warmups("Legs !!! 315 !!! Legs | Squat -> 3x5 @ 1r warmup none | Leg press -> 3x10 @ add20 equipment machine warmup 50% x10 !!! Leg press = 200 !!! warmup = bar x10, 50% x5"),
+                    vec![vec![], vec![100.0]]
+                );
+                assert_eq!(
+                    warmups("Legs !!! 315 !!! Legs | Squat -> 3x5 @ 1r !!!  !!! warmup = bar x10, 50% x5"),
+                    vec![vec![45.0, 155.0]]
+                );
+            }
+
+            #[test]
+            fn converts_to_other_units() {
+                let program = NewProgram::parse(
+                    "Legs !!! 225 !!! Legs | Squat -> 3x5 @ 1r+10 | Curl -> 3x10 @ add5 round 5 !!! Curl = 45",
+                )
+                .unwrap()
+                .start();
+                let converted = program.clone().convert_units(Unit::Kg);
+                assert_eq!(converted.unit(), Unit::Kg);
+                assert_eq!(converted.reference_weight, 102.5);
+                assert_eq!(
+                    converted.days[0].lifts.iter().map(|lift| lift.to_string()).collect::<Vec<_>>(),
+                    vec!["Squat -> 3x5 @ 1r+5", "Curl -> 3x10 @ add2.5 round 2.25 up"]
+                );
+                assert_eq!(converted.weights.values().copied().collect::<Vec<_>>(), vec![20.0]);
+                assert_eq!(program.clone().convert_units(Unit::Lb), program);
+            }
+
             #[test]
             fn increments_each_day_and_rolls_over() {
-                assert_eq!(start_gzcl_4day(100).current_day, 0);
+                assert_eq!(start_gzcl_4day(100.0).current_day, 0);
 
-                let before_completing_cycle = start_gzcl_4day(100)
-                    .complete_workout(&[NotCompleted; 5])
-                    .complete_workout(&[NotCompleted; 5]);
+                let before_completing_cycle = start_gzcl_4day(100.0)
+                    .complete_workout(&vec![NotCompleted; 5])
+                    .complete_workout(&vec![NotCompleted; 5]);
                 assert_eq!(before_completing_cycle.current_day, 2);
                 assert_eq!(before_completing_cycle.workouts_completed, 2);
 
-                let after_completing_cycle = start_gzcl_4day(100)
-                    .complete_workout(&[NotCompleted; 5])
-                    .complete_workout(&[NotCompleted; 5])
-                    .complete_workout(&[NotCompleted; 5])
-                    .complete_workout(&[NotCompleted; 5]);
+                let after_completing_cycle = start_gzcl_4day(100.0)
+                    .complete_workout(&vec![NotCompleted; 5])
+                    .complete_workout(&vec![NotCompleted; 5])
+                    .complete_workout(&vec![NotCompleted; 5])
+                    .complete_workout(&vec![NotCompleted; 5]);
                 assert_eq!(after_completing_cycle.current_day, 0);
                 assert_eq!(after_completing_cycle.workouts_completed, 4);
             }
@@ -0,0 +1,940 @@
+use crate::domain::lifting::{Lift, LiftAttemptResult, ATTRIBUTES, NAME_SEPARATORS};
+use crate::domain::notation::{decimal, quote, split_once_unquoted, suggest, unquote, NotationError};
+use crate::domain::rounding::Rounding;
+use crate::domain::set::Set;
+use crate::domain::weight_scheme::WeightScheme;
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// Decides how a weight moves from one cycle to the next, and what to prescribe along the way.
+pub trait Progression {
+    /// The lift to attempt, given where its progression is at.
+    fn prescribe(&self, lift: &Lift, _state: &ProgressionState) -> Prescription {
+        Prescription {
+            lift: lift.clone(),
+            weight_factor: 1.0,
+            note: None,
+        }
+    }
+
+    /// The next weight and state, given how the lifts it governs went.
+    /// `results` holds each lift as it was prescribed, alongside what happened.
+    fn progress(
+        &self,
+        weight: f64,
+        state: &ProgressionState,
+        results: &[(Lift, LiftAttemptResult)],
+    ) -> (f64, ProgressionState);
+}
+
+/// A lift to attempt, whose weight is to be scaled by `weight_factor`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prescription {
+    pub lift: Lift,
+    pub weight_factor: f64,
+    /// Where the progression is at, if it's worth telling.
+    pub note: Option<String>,
+}
+
+/// Where a lift (or reference weight) is in its progression, besides its weight.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ProgressionState {
+    /// What this means depends on the progression, e.g. the week of a 5/3/1 wave.
+    pub step: usize,
+    /// How many times in a row the progression has gone nowhere.
+    pub stalls: usize,
+}
+
+impl Display for ProgressionState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "step={} stalls={}", self.step, self.stalls)
+    }
+}
+
+impl ProgressionState {
+    /// ```
+    /// # use yawa::domain::progression::ProgressionState;
+    /// ProgressionState::parse("step=2 stalls=1").unwrap();
+    /// ProgressionState::parse("step=2").unwrap();
+    /// ProgressionState::parse("2").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        let mut state = ProgressionState::default();
+        notation
+            .split_whitespace()
+            .try_for_each(|entry| -> Result<()> {
+                match entry.split_once('=') {
+                    Some(("step", step)) => state.step = step.parse()?,
+                    Some(("stalls", stalls)) => state.stalls = stalls.parse()?,
+                    _ => return Err(anyhow!("Cannot parse progression state: {notation}")),
+                }
+                Ok(())
+            })?;
+        Ok(state)
+    }
+
+    /// Whether nothing moved between this state and the next, given the weights of both.
+    pub fn is_stalled(&self, weight: f64, next: &ProgressionState, next_weight: f64) -> bool {
+        self.step == next.step && weight == next_weight
+    }
+}
+
+/// What to do once a weight stops going up. Programs only deload if their settings say so.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum DeloadRule {
+    #[default]
+    Never,
+    /// Drop the weight by `percent` once it has stalled `stalls` times in a row.
+    After { stalls: usize, percent: usize },
+}
+
+impl Display for DeloadRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeloadRule::Never => write!(f, "never"),
+            DeloadRule::After { stalls, percent } => write!(f, "{percent}% after {stalls}"),
+        }
+    }
+}
+
+impl DeloadRule {
+    /// notation options:
+    /// ```
+    /// # use yawa::domain::progression::DeloadRule;
+    /// DeloadRule::parse("10% after 3").unwrap();
+    /// DeloadRule::parse("never").unwrap();
+    /// DeloadRule::parse("10%").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        let error = || anyhow!("Cannot parse deload: {notation}");
+        if notation.trim() == "never" {
+            return Ok(DeloadRule::Never);
+        }
+        let (percent, stalls) = notation.split_once(" after ").ok_or_else(error)?;
+        let percent: usize = percent
+            .trim()
+            .strip_suffix('%')
+            .ok_or_else(error)?
+            .parse()?;
+        let stalls: usize = stalls.trim().parse()?;
+        if percent > 100 || stalls == 0 {
+            return Err(error());
+        }
+        Ok(DeloadRule::After { stalls, percent })
+    }
+}
+
+/// A progression, as it is written in notation. `increment` overrides the lift's own increment.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProgressionScheme {
+    Linear { increment: Option<f64> },
+    Double { increment: Option<f64> },
+    Gzclp { tier: usize, increment: Option<f64> },
+    Wave531 { increment: Option<f64> },
+    AmrapAutoregulation,
+    EffortAutoregulation,
+    /// Raises rep targets instead of weight. `increment` is in reps.
+    Reps { increment: Option<f64> },
+    /// Raises the length of timed sets instead of weight. `increment` is in seconds.
+    Time { increment: Option<f64> },
+}
+
+impl Display for ProgressionScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let increment = match self {
+            ProgressionScheme::Linear { increment } => {
+                write!(f, "linear")?;
+                increment
+            }
+            ProgressionScheme::Double { increment } => {
+                write!(f, "double")?;
+                increment
+            }
+            ProgressionScheme::Gzclp { tier, increment } => {
+                write!(f, "gzclp-t{tier}")?;
+                increment
+            }
+            ProgressionScheme::Wave531 { increment } => {
+                write!(f, "531")?;
+                increment
+            }
+            ProgressionScheme::AmrapAutoregulation => return write!(f, "amrap"),
+            ProgressionScheme::EffortAutoregulation => return write!(f, "rpe"),
+            ProgressionScheme::Reps { increment } => {
+                write!(f, "reps")?;
+                increment
+            }
+            ProgressionScheme::Time { increment } => {
+                write!(f, "time")?;
+                increment
+            }
+        };
+        match increment {
+            Some(increment) => write!(f, "+{increment}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The kinds of progression there are, as they're written in notation.
+const PROGRESSIONS: [&str; 10] = [
+    "linear", "double", "gzclp-t1", "gzclp-t2", "gzclp-t3", "531", "amrap", "rpe", "reps", "time",
+];
+
+impl ProgressionScheme {
+    /// notation options:
+    /// ```
+    /// # use yawa::domain::progression::ProgressionScheme;
+    /// ProgressionScheme::parse("linear").unwrap();
+    /// ProgressionScheme::parse("linear+10").unwrap();
+    /// ProgressionScheme::parse("linear+2.5").unwrap();
+    /// ProgressionScheme::parse("double").unwrap();
+    /// ProgressionScheme::parse("gzclp-t1+10").unwrap();
+    /// ProgressionScheme::parse("531").unwrap();
+    /// ProgressionScheme::parse("amrap").unwrap();
+    /// ProgressionScheme::parse("rpe").unwrap();
+    /// ProgressionScheme::parse("reps").unwrap();
+    /// ProgressionScheme::parse("reps+2").unwrap();
+    /// ProgressionScheme::parse("time+5").unwrap();
+    /// ProgressionScheme::parse("reps+0.5").is_err();
+    /// ProgressionScheme::parse("exponential").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        let (kind, increment) = match notation.trim().split_once('+') {
+            Some((kind, increment)) => (kind, Some(increment.parse()?)),
+            None => (notation.trim(), None),
+        };
+        match kind {
+            "linear" => Ok(ProgressionScheme::Linear { increment }),
+            "double" => Ok(ProgressionScheme::Double { increment }),
+            "gzclp-t1" | "gzclp-t2" | "gzclp-t3" => Ok(ProgressionScheme::Gzclp {
+                tier: kind["gzclp-t".len()..].parse()?,
+                increment,
+            }),
+            "531" => Ok(ProgressionScheme::Wave531 { increment }),
+            "amrap" if increment.is_none() => Ok(ProgressionScheme::AmrapAutoregulation),
+            "rpe" if increment.is_none() => Ok(ProgressionScheme::EffortAutoregulation),
+            "reps" | "time" => {
+                if increment.is_some_and(|it: f64| it.fract() != 0.0 || it < 1.0) {
+                    return Err(anyhow!("Reps and seconds go up in whole numbers: {notation}"));
+                }
+                match kind {
+                    "reps" => Ok(ProgressionScheme::Reps { increment }),
+                    _ => Ok(ProgressionScheme::Time { increment }),
+                }
+            }
+            _ => Err(NotationError::new("Unknown progression", notation)
+                .suggesting(
+                    suggest(kind, &PROGRESSIONS).map(|it| notation.trim().replacen(kind, it, 1)),
+                )
+                .into()),
+        }
+    }
+
+    /// The same progression, with its own weight increment (if it has one) changed.
+    pub fn map_increment(&self, change: impl Fn(f64) -> f64) -> Self {
+        let mut scheme = self.clone();
+        match &mut scheme {
+            ProgressionScheme::Linear { increment }
+            | ProgressionScheme::Double { increment }
+            | ProgressionScheme::Gzclp { increment, .. }
+            | ProgressionScheme::Wave531 { increment } => *increment = increment.map(change),
+            ProgressionScheme::AmrapAutoregulation
+            | ProgressionScheme::EffortAutoregulation
+            | ProgressionScheme::Reps { .. }
+            | ProgressionScheme::Time { .. } => {}
+        }
+        scheme
+    }
+
+    /// How the weight increases when it does, given the increment of the lift (or reference),
+    /// and the rounding of weights it moves by a ratio.
+    pub fn progression(&self, default_increment: f64, rounding: Rounding) -> Box<dyn Progression> {
+        match self {
+            ProgressionScheme::Linear { increment } => Box::new(Linear {
+                increment: increment.unwrap_or(default_increment),
+            }),
+            ProgressionScheme::Double { increment } => Box::new(Double {
+                increment: increment.unwrap_or(default_increment),
+            }),
+            ProgressionScheme::Gzclp { tier, increment } => Box::new(Gzclp {
+                tier: *tier,
+                increment: increment.unwrap_or(default_increment),
+                rounding,
+            }),
+            ProgressionScheme::Wave531 { increment } => Box::new(Wave531 {
+                increment: increment.unwrap_or(default_increment),
+            }),
+            ProgressionScheme::AmrapAutoregulation => Box::new(AmrapAutoregulation {
+                fallback: Double {
+                    increment: default_increment,
+                },
+                rounding,
+            }),
+            ProgressionScheme::EffortAutoregulation => Box::new(EffortAutoregulation {
+                fallback: Double {
+                    increment: default_increment,
+                },
+                rounding,
+            }),
+            ProgressionScheme::Reps { increment } => Box::new(Targets {
+                increment: increment.map(|it| it as usize).unwrap_or(1),
+                timed: false,
+            }),
+            ProgressionScheme::Time { increment } => Box::new(Targets {
+                increment: increment.map(|it| it as usize).unwrap_or(5),
+                timed: true,
+            }),
+        }
+    }
+}
+
+fn all_completed(results: &[(Lift, LiftAttemptResult)]) -> bool {
+    results.iter().all(|(lift, result)| result.is_completed(lift))
+}
+
+fn all_completed_maximum_reps(results: &[(Lift, LiftAttemptResult)]) -> bool {
+    results
+        .iter()
+        .all(|(lift, result)| result.completed_maximum_reps(lift))
+}
+
+/// Adds weight whenever every set reaches its minimum reps.
+pub struct Linear {
+    pub increment: f64,
+}
+
+impl Progression for Linear {
+    fn progress(
+        &self,
+        weight: f64,
+        state: &ProgressionState,
+        results: &[(Lift, LiftAttemptResult)],
+    ) -> (f64, ProgressionState) {
+        if all_completed(results) {
+            (weight + self.increment, *state)
+        } else {
+            (weight, *state)
+        }
+    }
+}
+
+/// Adds weight only once every set reaches the top of its rep range. The default.
+pub struct Double {
+    pub increment: f64,
+}
+
+impl Progression for Double {
+    fn progress(
+        &self,
+        weight: f64,
+        state: &ProgressionState,
+        results: &[(Lift, LiftAttemptResult)],
+    ) -> (f64, ProgressionState) {
+        if all_completed_maximum_reps(results) {
+            (weight + self.increment, *state)
+        } else {
+            (weight, *state)
+        }
+    }
+}
+
+/// GZCLP tiers: T1 and T2 lifts add weight whenever their sets are completed,
+/// T3 lifts only once their last set reaches the top of its range.
+///
+/// A failed T1 or T2 lift moves on to its next stage, keeping the weight but splitting the work
+/// into more, smaller sets (T1: the lift's own sets, then 6x2, then 10x1; T2: the lift's own sets,
+/// then 3x8, then 3x6). Failing the last stage starts over from the first one at `RETEST` of the
+/// weight, rounded the way the lift's weights are.
+pub struct Gzclp {
+    pub tier: usize,
+    pub increment: f64,
+    pub rounding: Rounding,
+}
+
+impl Gzclp {
+    pub const RETEST: f64 = 0.85;
+
+    /// The sets of each stage after the first, as (number of sets, reps).
+    fn later_stages(&self) -> Vec<(usize, usize)> {
+        match self.tier {
+            1 => vec![(6, 2), (10, 1)],
+            2 => vec![(3, 8), (3, 6)],
+            _ => vec![],
+        }
+    }
+
+    /// The sets of a later stage. If the lift ends on an as many reps as possible set, so does
+    /// the stage. The sets keep the lift's effort target.
+    fn stage_sets(original: &[Set], (number_of_sets, reps): (usize, usize)) -> Vec<Set> {
+        let mut sets = vec![Set::Defined { reps }; number_of_sets];
+        let last_original = original.last().map(Set::without_effort);
+        if let (Some(Set::Amrap { .. }), Some(last)) = (last_original, sets.last_mut()) {
+            *last = Set::Amrap { minimum_reps: reps };
+        }
+        let effort = original.first().and_then(Set::effort);
+        sets.iter().map(|set| set.with_effort(effort)).collect()
+    }
+}
+
+impl Progression for Gzclp {
+    fn prescribe(&self, lift: &Lift, state: &ProgressionState) -> Prescription {
+        let later_stages = self.later_stages();
+        if later_stages.is_empty() {
+            return Prescription {
+                lift: lift.clone(),
+                weight_factor: 1.0,
+                note: None,
+            };
+        }
+        let stage = state.step.min(later_stages.len());
+        Prescription {
+            lift: Lift {
+                sets: match stage {
+                    0 => lift.sets.clone(),
+                    stage => Self::stage_sets(&lift.sets, later_stages[stage - 1]),
+                },
+                ..lift.clone()
+            },
+            weight_factor: 1.0,
+            note: Some(format!("stage {} of {}", stage + 1, later_stages.len() + 1)),
+        }
+    }
+
+    fn progress(
+        &self,
+        weight: f64,
+        state: &ProgressionState,
+        results: &[(Lift, LiftAttemptResult)],
+    ) -> (f64, ProgressionState) {
+        let succeeded = match self.tier {
+            1 | 2 => all_completed(results),
+            _ => all_completed_maximum_reps(results),
+        };
+        let stages = self.later_stages().len();
+        if succeeded {
+            (weight + self.increment, *state)
+        } else if stages == 0 {
+            (weight, *state)
+        } else if state.step < stages {
+            (
+                weight,
+                ProgressionState {
+                    step: state.step + 1,
+                    ..*state
+                },
+            )
+        } else {
+            (
+                self.rounding.round(weight * Self::RETEST),
+                ProgressionState { step: 0, ..*state },
+            )
+        }
+    }
+}
+
+/// 5/3/1 waves: weeks of 5s, 3s, then 5/3/1 at 85%, 90% and 95% of the weight (the training max),
+/// followed by a deload week at 60%. A week is repeated until its sets are completed, and the
+/// training max goes up after the deload.
+pub struct Wave531 {
+    pub increment: f64,
+}
+
+impl Wave531 {
+    const WEEKS: usize = 4;
+
+    fn week(state: &ProgressionState) -> (Vec<Set>, f64) {
+        let reps = |reps: usize| Set::Defined { reps };
+        let amrap = |minimum_reps: usize| Set::Amrap { minimum_reps };
+        match state.step % Self::WEEKS {
+            0 => (vec![reps(5), reps(5), amrap(5)], 0.85),
+            1 => (vec![reps(3), reps(3), amrap(3)], 0.9),
+            2 => (vec![reps(5), reps(3), amrap(1)], 0.95),
+            _ => (vec![reps(5), reps(5), reps(5)], 0.6),
+        }
+    }
+}
+
+impl Progression for Wave531 {
+    fn prescribe(&self, lift: &Lift, state: &ProgressionState) -> Prescription {
+        let (sets, weight_factor) = Self::week(state);
+        Prescription {
+            lift: Lift {
+                sets: sets.iter().map(|set| set.with_effort(lift.effort())).collect(),
+                ..lift.clone()
+            },
+            weight_factor,
+            note: None,
+        }
+    }
+
+    fn progress(
+        &self,
+        weight: f64,
+        state: &ProgressionState,
+        results: &[(Lift, LiftAttemptResult)],
+    ) -> (f64, ProgressionState) {
+        if !all_completed(results) {
+            return (weight, *state);
+        }
+        let step = (state.step + 1) % Self::WEEKS;
+        let weight = if step == 0 {
+            weight + self.increment
+        } else {
+            weight
+        };
+        (weight, ProgressionState { step, ..*state })
+    }
+}
+
+/// Moves the weight by how far the as many reps as possible sets beat (or missed) their target,
+/// comparing one rep maxes estimated with the Epley formula. Moves at most 10% at a time.
+/// Results without recorded reps fall back to double progression. The new weight is rounded
+/// with `rounding`.
+pub struct AmrapAutoregulation {
+    pub fallback: Double,
+    pub rounding: Rounding,
+}
+
+impl AmrapAutoregulation {
+    fn estimated_max_ratios(results: &[(Lift, LiftAttemptResult)]) -> Vec<f64> {
+        let epley = |reps: usize| 1.0 + (reps as f64 / 30.0);
+        results
+            .iter()
+            .flat_map(|(lift, result)| match result {
+                LiftAttemptResult::Performed { reps, .. } => lift
+                    .sets
+                    .iter()
+                    .zip(reps.iter())
+                    .filter(|(set, _)| matches!(set.without_effort(), Set::Amrap { .. }))
+                    .map(|(set, reps)| epley(*reps) / epley(set.minimum_reps()))
+                    .collect(),
+                _ => vec![],
+            })
+            .collect()
+    }
+}
+
+impl Progression for AmrapAutoregulation {
+    fn progress(
+        &self,
+        weight: f64,
+        state: &ProgressionState,
+        results: &[(Lift, LiftAttemptResult)],
+    ) -> (f64, ProgressionState) {
+        let ratios = Self::estimated_max_ratios(results);
+        if ratios.is_empty() {
+            return self.fallback.progress(weight, state, results);
+        }
+        let ratio = (ratios.iter().sum::<f64>() / ratios.len() as f64).clamp(0.9, 1.1);
+        (self.rounding.round(weight * ratio), *state)
+    }
+}
+
+/// Moves the weight by how far the effort recorded missed the lift's target: 2.5% for each
+/// point of RPE, down if it felt harder than planned and up if it felt easier. Moves at most 10%
+/// at a time. Results without a recorded RPE, or lifts without a target, fall back to double
+/// progression. The new weight is rounded with `rounding`.
+pub struct EffortAutoregulation {
+    pub fallback: Double,
+    pub rounding: Rounding,
+}
+
+impl EffortAutoregulation {
+    const PER_RPE: f64 = 0.025;
+
+    fn effort_ratios(results: &[(Lift, LiftAttemptResult)]) -> Vec<f64> {
+        results
+            .iter()
+            .filter_map(|(lift, result)| match (lift.effort(), result) {
+                (Some(target), LiftAttemptResult::Performed { rpe: Some(rpe), .. }) => {
+                    Some(1.0 + (target.rpe() - rpe) * Self::PER_RPE)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Progression for EffortAutoregulation {
+    fn progress(
+        &self,
+        weight: f64,
+        state: &ProgressionState,
+        results: &[(Lift, LiftAttemptResult)],
+    ) -> (f64, ProgressionState) {
+        let ratios = Self::effort_ratios(results);
+        if ratios.is_empty() {
+            return self.fallback.progress(weight, state, results);
+        }
+        let ratio = (ratios.iter().sum::<f64>() / ratios.len() as f64).clamp(0.9, 1.1);
+        (self.rounding.round(weight * ratio), *state)
+    }
+}
+
+/// Raises the targets of the lift's sets instead of its weight, by `increment` each time every
+/// set reaches its target: the reps of sets done for reps, or the seconds of timed sets.
+/// Which of the two it raises depends on `timed`. The weight stays where it is.
+pub struct Targets {
+    pub increment: usize,
+    pub timed: bool,
+}
+
+impl Progression for Targets {
+    fn prescribe(&self, lift: &Lift, state: &ProgressionState) -> Prescription {
+        let extra = state.step * self.increment;
+        Prescription {
+            lift: Lift {
+                sets: lift
+                    .sets
+                    .iter()
+                    .map(|set| match (set.without_effort(), self.timed) {
+                        (Set::Time { duration }, true) => Set::Time {
+                            duration: *duration + Duration::from_secs(extra as u64),
+                        },
+                        (Set::Defined { reps }, false) => Set::Defined { reps: reps + extra },
+                        (Set::Emom { reps }, false) => Set::Emom { reps: reps + extra },
+                        (Set::Amrap { minimum_reps }, false) => Set::Amrap {
+                            minimum_reps: minimum_reps + extra,
+                        },
+                        (Set::Range { minimum_reps, maximum_reps }, false) => Set::Range {
+                            minimum_reps: minimum_reps + extra,
+                            maximum_reps: maximum_reps + extra,
+                        },
+                        (set, _) => set.clone(),
+                    }
+                    .with_effort(set.effort()))
+                    .collect(),
+                ..lift.clone()
+            },
+            weight_factor: 1.0,
+            note: None,
+        }
+    }
+
+    fn progress(
+        &self,
+        weight: f64,
+        state: &ProgressionState,
+        results: &[(Lift, LiftAttemptResult)],
+    ) -> (f64, ProgressionState) {
+        if all_completed_maximum_reps(results) {
+            (weight, ProgressionState { step: state.step + 1, ..*state })
+        } else {
+            (weight, *state)
+        }
+    }
+}
+
+/// Swaps a lift for a harder variant once every one of its top sets reaches `reps`,
+/// like pullups for weighted pullups. The variant is done for the lift's original sets,
+/// with the lift's own equipment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Graduation {
+    pub reps: usize,
+    /// The name of the variant.
+    pub name: String,
+    pub weight: WeightScheme,
+    /// What the variant starts at, for variants that add to their previous weight.
+    pub starting_weight: Option<f64>,
+}
+
+impl Display for Graduation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let attributes: Vec<String> = ATTRIBUTES.iter().map(|it| format!(" {it} ")).collect();
+        let separators: Vec<&str> = NAME_SEPARATORS
+            .into_iter()
+            .chain([" @ "])
+            .chain(attributes.iter().map(String::as_str))
+            .collect();
+        write!(
+            f,
+            "at {} to {} @ {}",
+            self.reps,
+            quote(&self.name, &separators),
+            self.weight
+        )?;
+        if let Some(starting_weight) = self.starting_weight {
+            write!(f, " from {starting_weight}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Graduation {
+    /// Notation is the reps to reach, then the variant's name and weight, and what it starts
+    /// at if it adds to its previous weight:
+    /// ```
+    /// # use yawa::domain::progression::Graduation;
+    /// Graduation::parse("at 12 to Weighted Pullup @ add5 from 10").unwrap();
+    /// Graduation::parse("at 20 to Weighted Pushup @ bw+10").unwrap();
+    /// Graduation::parse("at 12 to Weighted Pullup @ add5").is_err();
+    /// Graduation::parse("at 20 to Weighted Pushup @ bw+10 from 10").is_err();
+    /// Graduation::parse("at 12 to Weighted Pullup").is_err();
+    /// Graduation::parse("12 to Weighted Pullup @ add5 from 10").is_err();
+    /// Graduation::parse("at 12 to \"Pullup @ the rings\" @ add5 from 10").unwrap();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        let error = || {
+            anyhow!("Cannot parse graduation: {notation}. Use like 'at 12 to Weighted Pullup @ add5 from 10'.")
+        };
+        let (reps, variant) = notation
+            .trim()
+            .strip_prefix("at ")
+            .and_then(|it| it.split_once(" to "))
+            .ok_or_else(error)?;
+        let (name, weight) = split_once_unquoted(variant, " @ ").ok_or_else(error)?;
+        let (weight, starting_weight) = match weight.split_once(" from ") {
+            Some((weight, starting_weight)) => {
+                (weight, Some(decimal(starting_weight.trim()).map_err(|_| error())?))
+            }
+            None => (weight, None),
+        };
+        let name = unquote(name)?;
+        let weight = WeightScheme::parse(weight.trim())?;
+        let adds_to_previous = matches!(weight, WeightScheme::LinearBasedOnPrevious { .. });
+        if adds_to_previous && starting_weight.is_none() {
+            return Err(anyhow!(
+                "Say what {name} starts at, like 'at 12 to Weighted Pullup @ add5 from 10': {notation}"
+            ));
+        }
+        if !adds_to_previous && starting_weight.is_some() {
+            return Err(anyhow!(
+                "Only variants that add to their previous weight start from one: {notation}"
+            ));
+        }
+        Ok(Graduation {
+            reps: reps.trim().parse().map_err(|_| error())?,
+            name,
+            weight,
+            starting_weight,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::lifting::LiftAttemptResult::{Completed, NotCompleted};
+
+    fn lift(notation: &str) -> Lift {
+        Lift::parse(notation).unwrap()
+    }
+
+    fn performed(reps: Vec<usize>) -> LiftAttemptResult {
+        LiftAttemptResult::performed(reps, None, None)
+    }
+
+    #[test]
+    fn can_parse_and_display_schemes() {
+        ["linear", "linear+10", "double", "double+5", "gzclp-t1", "gzclp-t3+5", "531", "531+10", "amrap", "rpe", "reps", "reps+2", "time+10"]
+            .iter()
+            .for_each(|notation| {
+                assert_eq!(
+                    ProgressionScheme::parse(notation).unwrap().to_string(),
+                    *notation
+                )
+            });
+        assert!(ProgressionScheme::parse("gzclp-t4").is_err());
+        assert!(ProgressionScheme::parse("amrap+5").is_err());
+        assert!(ProgressionScheme::parse("time+0").is_err());
+    }
+
+    #[test]
+    fn targets_raise_reps_or_seconds() {
+        let reps = Targets { increment: 1, timed: false };
+        let pullup = lift("Pullup -> 2x5,1x5+");
+        let step = |step| ProgressionState {
+            step,
+            ..Default::default()
+        };
+        assert_eq!(reps.prescribe(&pullup, &step(2)).lift.to_string(), "Pullup -> 2x7,1x7+");
+        assert_eq!(
+            reps.progress(0.0, &step(0), &[(pullup.clone(), performed(vec![5, 5, 8]))]),
+            (0.0, step(1))
+        );
+        assert_eq!(
+            reps.progress(0.0, &step(0), &[(pullup, performed(vec![5, 4, 8]))]),
+            (0.0, step(0))
+        );
+        let time = Targets { increment: 5, timed: true };
+        let plank = lift("Plank -> 1x30s,1x10 @ any");
+        assert_eq!(time.prescribe(&plank, &step(3)).lift.to_string(), "Plank -> 1x45s,1x10 @ any");
+    }
+
+    #[test]
+    fn can_parse_and_display_graduations() {
+        let graduation = Graduation::parse("at 12 to Weighted Pullup @ add5 from 10").unwrap();
+        assert_eq!(graduation.reps, 12);
+        assert_eq!(graduation.name, "Weighted Pullup");
+        assert_eq!(graduation.starting_weight, Some(10.0));
+        assert_eq!(graduation.to_string(), "at 12 to Weighted Pullup @ add5 from 10");
+    }
+
+    #[test]
+    fn linear_adds_weight_when_completed() {
+        let squat = lift("Squat -> 3x5-8 @ 1r");
+        let linear = Linear { increment: 10.0 };
+        let state = ProgressionState::default();
+        assert_eq!(
+            linear.progress(100.0, &state, &[(squat.clone(), performed(vec![5, 5, 6]))]),
+            (110.0, state)
+        );
+        assert_eq!(
+            linear.progress(100.0, &state, &[(squat, performed(vec![5, 5, 4]))]),
+            (100.0, state)
+        );
+    }
+
+    #[test]
+    fn double_adds_weight_at_top_of_range() {
+        let curl = lift("Curl -> 3x8-12 @ add5");
+        let double = Double { increment: 5.0 };
+        let state = ProgressionState::default();
+        assert_eq!(
+            double.progress(20.0, &state, &[(curl.clone(), performed(vec![12, 12, 11]))]),
+            (20.0, state)
+        );
+        assert_eq!(
+            double.progress(20.0, &state, &[(curl, performed(vec![12, 12, 12]))]),
+            (25.0, state)
+        );
+    }
+
+    #[test]
+    fn gzclp_tiers_judge_differently() {
+        let t3 = lift("Lat pulldown -> 2x15,1x15-25 @ add5");
+        let rounding = Rounding::parse("5").unwrap();
+        let state = ProgressionState::default();
+        let result = [(t3, performed(vec![15, 15, 20]))];
+        assert_eq!(
+            Gzclp { tier: 2, increment: 5.0, rounding }.progress(50.0, &state, &result),
+            (55.0, state)
+        );
+        assert_eq!(
+            Gzclp { tier: 3, increment: 5.0, rounding }.progress(50.0, &state, &result),
+            (50.0, state)
+        );
+    }
+
+    #[test]
+    fn gzclp_moves_through_stages_on_failure() {
+        let rounding = Rounding::parse("5").unwrap();
+        let t1 = Gzclp { tier: 1, increment: 10.0, rounding };
+        let squat = lift("Squat -> 4x3,1x3+ @ 1r[squat]");
+        let stage = |step| ProgressionState {
+            step,
+            ..Default::default()
+        };
+        let prescribed = |step| t1.prescribe(&squat, &stage(step));
+        assert_eq!(prescribed(0).lift, squat);
+        assert_eq!(prescribed(0).note, Some("stage 1 of 3".to_string()));
+        assert_eq!(prescribed(1).lift.to_string(), "Squat -> 5x2,1x2+ @ 1r[squat]");
+        assert_eq!(prescribed(2).lift.to_string(), "Squat -> 9x1,1x1+ @ 1r[squat]");
+        assert_eq!(prescribed(2).note, Some("stage 3 of 3".to_string()));
+        let failed = [(squat.clone(), NotCompleted)];
+        assert_eq!(t1.progress(200.0, &stage(0), &failed), (200.0, stage(1)));
+        assert_eq!(t1.progress(200.0, &stage(1), &failed), (200.0, stage(2)));
+        assert_eq!(t1.progress(200.0, &stage(2), &failed), (170.0, stage(0)));
+        // Retested at a weight that can be loaded, not just a whole number.
+        assert_eq!(t1.progress(225.0, &stage(2), &failed), (195.0, stage(0)));
+        let kg = Gzclp { rounding: Rounding::parse("2.5 nearest").unwrap(), ..t1 };
+        assert_eq!(kg.progress(105.0, &stage(2), &failed), (90.0, stage(0)));
+        let completed = [(prescribed(1).lift, performed(vec![2; 6]))];
+        assert_eq!(t1.progress(200.0, &stage(1), &completed), (210.0, stage(1)));
+
+        let t2 = Gzclp { tier: 2, increment: 5.0, rounding };
+        let row = lift("Row -> 3x10 @ add5");
+        assert_eq!(t2.prescribe(&row, &stage(2)).lift.to_string(), "Row -> 3x6 @ add5");
+        let t3 = Gzclp { tier: 3, increment: 5.0, rounding };
+        assert_eq!(t3.prescribe(&row, &stage(0)).note, None);
+        assert_eq!(
+            t3.progress(50.0, &stage(0), &[(row, NotCompleted)]),
+            (50.0, stage(0))
+        );
+    }
+
+    #[test]
+    fn wave_531_moves_through_weeks() {
+        let wave = Wave531 { increment: 10.0 };
+        let squat = lift("Squat -> 3x5+ @ 1r[squat]");
+        let week = |step| ProgressionState {
+            step,
+            ..Default::default()
+        };
+        let prescription = wave.prescribe(&squat, &week(1));
+        assert_eq!(prescription.lift.to_string(), "Squat -> 2x3,1x3+ @ 1r[squat]");
+        assert_eq!(prescription.weight_factor, 0.9);
+        let prescription = wave.prescribe(&squat, &week(3));
+        assert_eq!(prescription.lift.to_string(), "Squat -> 3x5 @ 1r[squat]");
+        assert_eq!(prescription.weight_factor, 0.6);
+        let completed = [(squat.clone(), Completed { completed_maximum_reps: false })];
+        assert_eq!(wave.progress(200.0, &week(0), &completed), (200.0, week(1)));
+        assert_eq!(wave.progress(200.0, &week(3), &completed), (210.0, week(0)));
+        assert_eq!(
+            wave.progress(200.0, &week(2), &[(squat, NotCompleted)]),
+            (200.0, week(2))
+        );
+    }
+
+    #[test]
+    fn amrap_autoregulation_follows_reps() {
+        let amrap = AmrapAutoregulation {
+            fallback: Double { increment: 5.0 },
+            rounding: Rounding::parse("1 nearest").unwrap(),
+        };
+        let bench = lift("Bench press -> 4x3,1x3+ @ 1r");
+        let state = ProgressionState::default();
+        assert_eq!(
+            amrap.progress(200.0, &state, &[(bench.clone(), performed(vec![3, 3, 3, 3, 6]))]),
+            (218.0, state)
+        );
+        assert_eq!(
+            amrap.progress(200.0, &state, &[(bench.clone(), performed(vec![3, 3, 3, 3, 3]))]),
+            (200.0, state)
+        );
+        assert_eq!(
+            amrap.progress(200.0, &state, &[(bench.clone(), performed(vec![3, 3, 3, 3, 30]))]),
+            (220.0, state)
+        );
+        assert_eq!(
+            amrap.progress(200.0, &state, &[(bench.clone(), Completed { completed_maximum_reps: true })]),
+            (205.0, state)
+        );
+        let plates = AmrapAutoregulation {
+            rounding: Rounding::parse("2.5 nearest").unwrap(),
+            ..amrap
+        };
+        assert_eq!(
+            plates.progress(200.0, &state, &[(bench, performed(vec![3, 3, 3, 3, 6]))]),
+            (217.5, state)
+        );
+    }
+
+    #[test]
+    fn effort_autoregulation_follows_rpe() {
+        let effort = EffortAutoregulation {
+            fallback: Double { increment: 5.0 },
+            rounding: Rounding::parse("1 nearest").unwrap(),
+        };
+        let squat = lift("Squat -> 3x5 @ RPE8 @ 1r");
+        let felt = |rpe: f64| LiftAttemptResult::performed(vec![5, 5, 5], None, Some(rpe));
+        let state = ProgressionState::default();
+        assert_eq!(effort.progress(200.0, &state, &[(squat.clone(), felt(8.0))]), (200.0, state));
+        assert_eq!(effort.progress(200.0, &state, &[(squat.clone(), felt(9.0))]), (195.0, state));
+        assert_eq!(effort.progress(200.0, &state, &[(squat.clone(), felt(6.0))]), (210.0, state));
+        assert_eq!(effort.progress(200.0, &state, &[(squat.clone(), felt(1.0))]), (220.0, state));
+        let reserve = lift("Squat -> 3x5 @ 2RIR @ 1r");
+        assert_eq!(effort.progress(200.0, &state, &[(reserve, felt(10.0))]), (190.0, state));
+        assert_eq!(
+            effort.progress(200.0, &state, &[(squat.clone(), performed(vec![5, 5, 5]))]),
+            (205.0, state)
+        );
+        let plates = EffortAutoregulation {
+            rounding: Rounding::parse("5 down").unwrap(),
+            ..effort
+        };
+        assert_eq!(plates.progress(200.0, &state, &[(squat, felt(8.5))]), (195.0, state));
+    }
+}
@@ -0,0 +1,124 @@
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+
+/// How a computed weight is turned into one that can actually be loaded.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rounding {
+    /// The smallest step the equipment allows, like 5 for a barbell with 2.5 plates.
+    pub increment: f64,
+    pub direction: RoundingDirection,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RoundingDirection {
+    Up,
+    Down,
+    Nearest,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Rounding {
+            increment: 5.0,
+            direction: RoundingDirection::Up,
+        }
+    }
+}
+
+impl Display for Rounding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let direction = match self.direction {
+            RoundingDirection::Up => "up",
+            RoundingDirection::Down => "down",
+            RoundingDirection::Nearest => "nearest",
+        };
+        write!(f, "{} {}", self.increment, direction)
+    }
+}
+
+impl Rounding {
+    /// Notation is the increment, optionally followed by a direction (up, if not given):
+    /// ```
+    /// # use yawa::domain::rounding::Rounding;
+    /// Rounding::parse("5").unwrap();
+    /// Rounding::parse("2.5 nearest").unwrap();
+    /// Rounding::parse("1.25 down").unwrap();
+    /// Rounding::parse("0").is_err();
+    /// Rounding::parse("inf").is_err();
+    /// Rounding::parse("5 sideways").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        let mut words = notation.split_whitespace();
+        let increment: f64 = words
+            .next()
+            .ok_or(anyhow!("Cannot parse rounding: {notation}"))?
+            .parse()?;
+        if !increment.is_finite() || increment <= 0.0 {
+            return Err(anyhow!("Rounding increment must be a number above zero: {notation}"));
+        }
+        let direction = match words.next() {
+            None | Some("up") => RoundingDirection::Up,
+            Some("down") => RoundingDirection::Down,
+            Some("nearest") => RoundingDirection::Nearest,
+            Some(other) => return Err(anyhow!("Unknown rounding direction: {other}")),
+        };
+        if words.next().is_some() {
+            return Err(anyhow!("Cannot parse rounding: {notation}"));
+        }
+        Ok(Rounding {
+            increment,
+            direction,
+        })
+    }
+
+    /// The weight, moved onto a multiple of the increment.
+    pub fn round(&self, weight: f64) -> f64 {
+        // Leave some room for floating point error, so 0.85 * 100 is still 85.
+        let tolerance = 1e-9;
+        let steps = weight / self.increment;
+        let steps = match self.direction {
+            RoundingDirection::Up => (steps - tolerance).ceil(),
+            RoundingDirection::Down => (steps + tolerance).floor(),
+            RoundingDirection::Nearest => steps.round(),
+        };
+        // Adding zero turns a -0 (like 0 rounded up) into 0, so it isn't shown as '-0'.
+        steps * self.increment + 0.0
+    }
+
+    pub fn down(&self) -> Self {
+        Rounding {
+            direction: RoundingDirection::Down,
+            ..*self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_in_each_direction() {
+        let up = Rounding::parse("5").unwrap();
+        assert_eq!(up.round(101.0), 105.0);
+        assert_eq!(up.round(0.85 * 100.0), 85.0);
+        let down = Rounding::parse("2.5 down").unwrap();
+        assert_eq!(down.round(101.0), 100.0);
+        let nearest = Rounding::parse("1.25 nearest").unwrap();
+        assert_eq!(nearest.round(61.3), 61.25);
+        assert_eq!(nearest.round(61.8), 61.25);
+        assert_eq!(nearest.round(62.0), 62.5);
+        [up, down, nearest].iter().for_each(|rounding| {
+            assert_eq!(rounding.round(0.0).to_string(), "0");
+            assert!(rounding.round(0.0).is_sign_positive());
+        });
+    }
+
+    #[test]
+    fn can_parse_and_display() {
+        ["5 up", "2.5 down", "7.5 nearest"].iter().for_each(|notation| {
+            assert_eq!(Rounding::parse(notation).unwrap().to_string(), *notation)
+        });
+        assert_eq!(Rounding::parse("5").unwrap(), Rounding::default());
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
 use std::time::Duration;
 use anyhow::anyhow;
+use crate::domain::notation::{count, decimal, NotationError};
+use crate::domain::timing::{display_duration, parse_duration};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Set {
@@ -18,6 +21,100 @@ pub enum Set {
     Time {
         duration: Duration,
     },
+    /// Covering a distance, like a sled push or a run. What's recorded is how many seconds it took.
+    Distance {
+        meters: usize,
+    },
+    /// Burning calories on a rower or bike. What's recorded is how many seconds it took.
+    Calories {
+        calories: usize,
+    },
+    /// Every minute on the minute: the reps at the start of a minute, resting for the rest of it.
+    Emom {
+        reps: usize,
+    },
+    /// As many rounds as possible within the time cap. What's recorded is the rounds done.
+    Rounds {
+        cap: Duration,
+    },
+    /// The reps, then as many reps as possible after each drop to a lighter weight,
+    /// without resting in between. Each drop is `percent` lighter than the weight before it,
+    /// or a fifth lighter, if not given.
+    Drop {
+        reps: usize,
+        drops: usize,
+        percent: Option<f64>,
+    },
+    /// The reps, then as many reps as possible in each mini-set after a few breaths of rest.
+    RestPause {
+        reps: usize,
+        mini_sets: usize,
+    },
+    /// The reps of each cluster, with a short rest between them.
+    Cluster {
+        reps: Vec<usize>,
+    },
+    /// A set with a target for how hard it should feel.
+    WithEffort {
+        set: Box<Set>,
+        effort: Effort,
+    },
+}
+
+/// The RPEs a set can have, from no effort at all to a set taken to failure.
+pub const RPE_SCALE: RangeInclusive<f64> = 1.0..=10.0;
+
+/// How hard a set should feel: a rate of perceived exertion out of 10,
+/// or how many reps should be left in reserve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Effort {
+    Rpe(f64),
+    Rir(usize),
+}
+
+impl Display for Effort {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Effort::Rpe(rpe) => write!(f, "RPE{rpe}"),
+            Effort::Rir(rir) => write!(f, "{rir}RIR"),
+        }
+    }
+}
+
+impl Effort {
+    /// notation options:
+    /// ```
+    /// # use yawa::domain::set::Effort;
+    /// Effort::parse("RPE8").unwrap();
+    /// Effort::parse("RPE7.5").unwrap();
+    /// Effort::parse("2RIR").unwrap();
+    /// Effort::parse("RPE11").is_err();
+    /// Effort::parse("RPE0").is_err();
+    /// Effort::parse("10RIR").is_err();
+    /// Effort::parse("1r").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> anyhow::Result<Self> {
+        let notation = notation.trim();
+        let effort = if let Some(rpe) = notation.strip_prefix("RPE") {
+            Effort::Rpe(rpe.parse()?)
+        } else if let Some(rir) = notation.strip_suffix("RIR") {
+            Effort::Rir(rir.parse()?)
+        } else {
+            return Err(anyhow!("Invalid effort notation: {notation}"));
+        };
+        if !RPE_SCALE.contains(&effort.rpe()) {
+            return Err(anyhow!("RPE must be from 1 to 10, and RIR from 0 to 9: {notation}"));
+        }
+        Ok(effort)
+    }
+
+    /// The effort as an RPE, taking reps in reserve as what's left from 10.
+    pub fn rpe(&self) -> f64 {
+        match self {
+            Effort::Rpe(rpe) => *rpe,
+            Effort::Rir(rir) => 10.0 - *rir as f64,
+        }
+    }
 }
 
 impl Display for Set {
@@ -31,11 +128,107 @@ impl Display for Set {
             Set::Any => write!(f, "Any"),
             Set::Defined { reps } => write!(f, "{reps}"),
             Set::Time { duration } => write!(f, "{}s", duration.as_secs()),
+            Set::Distance { meters } if *meters >= 1000 && meters % 1000 == 0 => {
+                write!(f, "{}km", meters / 1000)
+            }
+            Set::Distance { meters } => write!(f, "{meters}m"),
+            Set::Calories { calories } => write!(f, "{calories}cal"),
+            Set::Emom { reps } => write!(f, "EMOM({reps})"),
+            Set::Rounds { cap } => write!(f, "AMRAP({})", display_duration(*cap)),
+            Set::Drop {
+                reps,
+                drops,
+                percent: None,
+            } => write!(f, "{reps} drop {drops}"),
+            Set::Drop {
+                reps,
+                drops,
+                percent: Some(percent),
+            } => write!(f, "{reps} drop {drops} by {percent}%"),
+            Set::RestPause { reps, mini_sets } => write!(f, "{reps}+rp{mini_sets}"),
+            Set::Cluster { reps } => write!(
+                f,
+                "({})",
+                reps.iter()
+                    .map(|it| it.to_string())
+                    .collect::<Vec<String>>()
+                    .join("+")
+            ),
+            Set::WithEffort { set, effort } => write!(f, "{set} @ {effort}"),
         };
     }
 }
 
 impl Set {
+    /// The fewest reps (or seconds, for timed sets) that count as completing this set.
+    /// Conditioning sets that record something else count as completed once anything is recorded.
+    pub fn minimum_reps(&self) -> usize {
+        match self {
+            Set::WithEffort { set, .. } => set.minimum_reps(),
+            Set::Amrap { minimum_reps } => *minimum_reps,
+            Set::Range { minimum_reps, .. } => *minimum_reps,
+            Set::Any => 0,
+            Set::Defined { reps } => *reps,
+            Set::Time { duration } => duration.as_secs() as usize,
+            Set::Emom { reps } => *reps,
+            Set::Drop { reps, .. } | Set::RestPause { reps, .. } => *reps,
+            Set::Cluster { reps } => reps.iter().sum(),
+            Set::Distance { .. } | Set::Calories { .. } | Set::Rounds { .. } => 1,
+        }
+    }
+
+    /// The reps (or seconds, for timed sets) to reach for this set to count as maxed out.
+    /// As many reps as possible sets are maxed out once their minimum is reached.
+    pub fn maximum_reps(&self) -> usize {
+        match self {
+            Set::WithEffort { set, .. } => set.maximum_reps(),
+            Set::Range { maximum_reps, .. } => *maximum_reps,
+            _ => self.minimum_reps(),
+        }
+    }
+
+    /// The reps each part asks for, for sets done in parts, with `None` for parts done to
+    /// failure. Empty for sets done in one go.
+    pub fn parts(&self) -> Vec<Option<usize>> {
+        match self {
+            Set::WithEffort { set, .. } => set.parts(),
+            Set::Drop { reps, drops, .. } => [vec![Some(*reps)], vec![None; *drops]].concat(),
+            Set::RestPause { reps, mini_sets } => {
+                [vec![Some(*reps)], vec![None; *mini_sets]].concat()
+            }
+            Set::Cluster { reps } => reps.iter().map(|it| Some(*it)).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// How hard the set should feel, if it has a target.
+    pub fn effort(&self) -> Option<Effort> {
+        match self {
+            Set::WithEffort { effort, .. } => Some(*effort),
+            _ => None,
+        }
+    }
+
+    /// The set itself, without its effort target.
+    pub fn without_effort(&self) -> &Set {
+        match self {
+            Set::WithEffort { set, .. } => set,
+            set => set,
+        }
+    }
+
+    /// The same set, with the effort target given instead of the one it had (if any).
+    pub fn with_effort(&self, effort: Option<Effort>) -> Set {
+        let set = self.without_effort().clone();
+        match effort {
+            Some(effort) => Set::WithEffort {
+                set: Box::new(set),
+                effort,
+            },
+            None => set,
+        }
+    }
+
     /// notation options:
     /// ```
     /// # use yawa::domain::set::Set;
@@ -45,28 +238,155 @@ impl Set {
     /// Set::parse("10").unwrap();
     /// Set::parse("5s").unwrap();
     /// ```
+    ///
+    /// Conditioning sets are distances, calories, minutes of EMOM with their reps, or a time cap
+    /// to do as many rounds as possible in:
+    /// ```
+    /// # use yawa::domain::set::Set;
+    /// Set::parse("400m").unwrap();
+    /// Set::parse("2km").unwrap();
+    /// Set::parse("20cal").unwrap();
+    /// Set::parse("EMOM(5)").unwrap();
+    /// Set::parse("AMRAP(12min)").unwrap();
+    /// Set::parse("EMOM(5").is_err();
+    /// ```
+    ///
+    /// Sets done in parts are drop sets with how many drops (and maybe how much lighter each
+    /// drop is), rest-pause sets with how many mini-sets, or clusters with the reps of each:
+    /// ```
+    /// # use yawa::domain::set::Set;
+    /// Set::parse("10 drop 2").unwrap();
+    /// Set::parse("10 drop 2 by 25%").unwrap();
+    /// Set::parse("10 drop 2 by 100%").is_err();
+    /// Set::parse("8+rp3").unwrap();
+    /// Set::parse("(2+2+2)").unwrap();
+    /// Set::parse("(2+2").is_err();
+    /// ```
+    ///
+    /// Any set can have a target for how hard it should feel (see `Effort::parse()`):
+    /// ```
+    /// # use yawa::domain::set::Set;
+    /// Set::parse("5 @ RPE8").unwrap();
+    /// Set::parse("3+ @ 2RIR").unwrap();
+    /// Set::parse("5 @ RPE8 @ 2RIR").is_err();
+    /// ```
     pub fn parse(notation: &str) -> anyhow::Result<Self> {
+        Self::parse_set(notation).map_err(|_| {
+            NotationError::new("Cannot parse set", notation)
+                .suggesting(Self::fix(notation))
+                .into()
+        })
+    }
+
+    /// What the notation probably meant, if changing its case or spacing, or writing it the way
+    /// sets are written, makes it parse.
+    fn fix(notation: &str) -> Option<String> {
+        let lowercase = notation.to_lowercase();
+        let uppercase_prefix = |prefix: &str| {
+            lowercase
+                .strip_prefix(&prefix.to_lowercase())
+                .map(|rest| format!("{prefix}{rest}"))
+        };
+        [
+            uppercase_prefix("Any"),
+            uppercase_prefix("EMOM("),
+            uppercase_prefix("AMRAP("),
+            Some(
+                lowercase
+                    .replace("drop", " drop ")
+                    .split_whitespace()
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            ),
+            Some(notation.replace(' ', "")),
+            Some(format!("({notation})")),
+            lowercase
+                .split_once("sec")
+                .map(|(seconds, _)| format!("{}s", seconds.trim())),
+            lowercase.split_once("rp").map(|(reps, mini_sets)| {
+                format!("{}+rp{mini_sets}", reps.trim_end_matches('+'))
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|fix| fix != notation && Self::parse_set(fix).is_ok())
+    }
+
+    fn parse_set(notation: &str) -> anyhow::Result<Self> {
         let error = "Invalid notation";
-        return if notation == "Any" {
+        let inside = |prefix: &str| {
+            notation
+                .strip_prefix(prefix)
+                .map(|it| it.strip_suffix(')').ok_or(anyhow!(error)))
+        };
+        return if let Some((set, effort)) = notation.split_once(" @ ") {
+            Ok(Self::parse_set(set.trim())?.with_effort(Some(Effort::parse(effort)?)))
+        } else if notation == "Any" {
             Ok(Set::Any)
-        } else if notation.contains('-') {
-            let mut split = notation.split('-');
-            Ok(Set::Range {
-                minimum_reps: split.next().ok_or(anyhow!(error))?.parse()?,
-                maximum_reps: split.next().ok_or(anyhow!(error))?.parse()?,
+        } else if let Some((reps, drops)) = notation.split_once(" drop ") {
+            let (drops, percent) = match drops.split_once(" by ") {
+                Some((drops, percent)) => {
+                    let percent = decimal(percent.strip_suffix('%').ok_or(anyhow!(error))?)?;
+                    if !(percent > 0.0 && percent < 100.0) {
+                        return Err(anyhow!(error));
+                    }
+                    (drops, Some(percent))
+                }
+                None => (drops, None),
+            };
+            Ok(Set::Drop {
+                reps: count(reps)?,
+                drops: count(drops)?,
+                percent,
+            })
+        } else if let Some((reps, mini_sets)) = notation.split_once("+rp") {
+            Ok(Set::RestPause {
+                reps: count(reps)?,
+                mini_sets: count(mini_sets)?,
             })
-        } else if notation.contains('+') {
-            let rep_string = notation.split('+').next().ok_or(anyhow!(error))?;
+        } else if let Some(reps) = inside("(") {
+            Ok(Set::Cluster {
+                reps: reps?
+                    .split('+')
+                    .map(count)
+                    .collect::<anyhow::Result<Vec<usize>>>()?,
+            })
+        } else if let Some(reps) = inside("EMOM(") {
+            Ok(Set::Emom {
+                reps: count(reps?)?,
+            })
+        } else if let Some(cap) = inside("AMRAP(") {
+            Ok(Set::Rounds {
+                cap: parse_duration(cap?)?,
+            })
+        } else if let Some(calories) = notation.strip_suffix("cal") {
+            Ok(Set::Calories {
+                calories: count(calories)?,
+            })
+        } else if let Some(kilometers) = notation.strip_suffix("km").filter(|it| !it.starts_with('-')) {
+            Ok(Set::Distance {
+                meters: (decimal(kilometers)? * 1000.0).round() as usize,
+            })
+        } else if let Some(meters) = notation.strip_suffix('m') {
+            Ok(Set::Distance {
+                meters: count(meters)?,
+            })
+        } else if let Some(seconds) = notation.strip_suffix('s') {
+            Ok(Set::Time {
+                duration: Duration::from_secs(count(seconds)? as u64),
+            })
+        } else if let Some(minimum_reps) = notation.strip_suffix('+') {
             Ok(Set::Amrap {
-                minimum_reps: rep_string.parse()?,
+                minimum_reps: count(minimum_reps)?,
+            })
+        } else if let Some((minimum_reps, maximum_reps)) = notation.split_once('-') {
+            Ok(Set::Range {
+                minimum_reps: count(minimum_reps)?,
+                maximum_reps: count(maximum_reps)?,
             })
-        } else if notation.contains('s') {
-            let seconds_str = notation.split('s').next().ok_or(anyhow!(error))?;
-            let duration = Duration::from_secs(seconds_str.parse()?);
-            Ok(Set::Time { duration })
         } else {
             Ok(Set::Defined {
-                reps: notation.parse()?,
+                reps: count(notation)?,
             })
         };
     }
@@ -75,7 +395,8 @@ impl Set {
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
-    use crate::domain::set::Set;
+    use crate::domain::notation::NotationError;
+    use crate::domain::set::{Effort, Set};
 
     #[test]
     fn can_create_rep_schemes() {
@@ -98,6 +419,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_parse_and_display_efforts() {
+        assert_eq!(Effort::parse("RPE8").unwrap(), Effort::Rpe(8.0));
+        assert_eq!(Effort::parse("2RIR").unwrap(), Effort::Rir(2));
+        assert_eq!(Effort::parse("2RIR").unwrap().rpe(), 8.0);
+        assert_eq!(Effort::parse("RPE8.5").unwrap().to_string(), "RPE8.5");
+        assert_eq!(Effort::parse("3RIR").unwrap().to_string(), "3RIR");
+        assert!(Effort::parse("RPE0").is_err());
+        let set = Set::parse("3+ @ RPE8").unwrap();
+        assert_eq!(set.effort(), Some(Effort::Rpe(8.0)));
+        assert_eq!(set.without_effort(), &Set::Amrap { minimum_reps: 3 });
+        assert_eq!(set.minimum_reps(), 3);
+        assert_eq!(set.to_string(), "3+ @ RPE8");
+    }
+
     #[test]
     fn can_display_rep_schemes() {
         assert_eq!(format!("{}", Set::parse("2-3").unwrap()), "2-3");
@@ -106,4 +442,88 @@ mod tests {
         assert_eq!(format!("{}", Set::parse("Any").unwrap()), "Any");
         assert_eq!(format!("{}", Set::parse("2s").unwrap()), "2s");
     }
+
+    #[test]
+    fn can_parse_and_display_conditioning() {
+        assert_eq!(Set::parse("400m").unwrap(), Set::Distance { meters: 400 });
+        assert_eq!(Set::parse("1.5km").unwrap(), Set::Distance { meters: 1500 });
+        assert_eq!(
+            Set::parse("AMRAP(12min)").unwrap(),
+            Set::Rounds {
+                cap: Duration::from_secs(720)
+            }
+        );
+        ["400m", "2km", "1500m", "20cal", "EMOM(5)", "AMRAP(12min)", "AMRAP(7min30s)"]
+            .iter()
+            .for_each(|notation| assert_eq!(Set::parse(notation).unwrap().to_string(), *notation));
+        assert!(Set::parse("AMRAP()").is_err());
+        assert!(Set::parse("fivem").is_err());
+    }
+
+    #[test]
+    fn can_parse_and_display_sets_done_in_parts() {
+        assert_eq!(
+            Set::parse("10 drop 2").unwrap(),
+            Set::Drop { reps: 10, drops: 2, percent: None }
+        );
+        assert_eq!(
+            Set::parse("10 drop 2 by 25%").unwrap(),
+            Set::Drop { reps: 10, drops: 2, percent: Some(25.0) }
+        );
+        assert_eq!(Set::parse("8+rp3").unwrap(), Set::RestPause { reps: 8, mini_sets: 3 });
+        assert_eq!(Set::parse("(2+2+2)").unwrap(), Set::Cluster { reps: vec![2, 2, 2] });
+        ["10 drop 2", "10 drop 3 by 12.5%", "8+rp3", "(2+2+2)", "(3+2+1)"]
+            .iter()
+            .for_each(|notation| assert_eq!(Set::parse(notation).unwrap().to_string(), *notation));
+        assert_eq!(Set::parse("(3+2+1)").unwrap().minimum_reps(), 6);
+        assert_eq!(Set::parse("8+rp3").unwrap().minimum_reps(), 8);
+        assert_eq!(
+            Set::parse("10 drop 2").unwrap().parts(),
+            vec![Some(10), None, None]
+        );
+        assert!(Set::parse("8").unwrap().parts().is_empty());
+        assert!(Set::parse("8+rp").is_err());
+        assert!(Set::parse("()").is_err());
+    }
+
+    #[test]
+    fn suggests_fixes() {
+        let suggestion = |notation: &str| {
+            Set::parse(notation)
+                .unwrap_err()
+                .downcast::<NotationError>()
+                .unwrap()
+                .suggestion
+        };
+        assert_eq!(suggestion("emom(5)"), Some("EMOM(5)".to_string()));
+        assert_eq!(suggestion("10drop2"), Some("10 drop 2".to_string()));
+        assert_eq!(suggestion("8rp3"), Some("8+rp3".to_string()));
+        assert_eq!(suggestion("30 s"), Some("30s".to_string()));
+        assert_eq!(suggestion("five"), None);
+    }
+
+    #[test]
+    fn parses_strictly() {
+        ["30sec", "2+2+2", "2-3-4", "+3", "3+4+", "5ss", "-1km", "1e3m", "(2+x)"]
+            .iter()
+            .for_each(|notation| assert!(Set::parse(notation).is_err(), "{notation}"));
+        let suggestion = |notation: &str| {
+            Set::parse(notation)
+                .unwrap_err()
+                .downcast::<NotationError>()
+                .unwrap()
+                .suggestion
+        };
+        assert_eq!(suggestion("30sec"), Some("30s".to_string()));
+        assert_eq!(suggestion("2+2+2"), Some("(2+2+2)".to_string()));
+        [
+            "Any", "5", "8-12", "3+", "45s", "400m", "2km", "20cal", "EMOM(5)", "AMRAP(7min30s)",
+            "10 drop 2", "8+rp3", "(2+2+2)",
+        ]
+        .iter()
+        .for_each(|notation| {
+            let set = Set::parse(notation).unwrap();
+            assert_eq!(Set::parse(&set.to_string()).unwrap(), set);
+        });
+    }
 }
\ No newline at end of file
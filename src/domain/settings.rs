@@ -0,0 +1,160 @@
+use crate::domain::notation::{suggest, NotationContext, NotationError};
+use crate::domain::plates::{PlateInventory, Plates};
+use crate::domain::progression::{DeloadRule, ProgressionScheme};
+use crate::domain::rounding::Rounding;
+use crate::domain::units::Unit;
+use crate::domain::warmups::WarmupScheme;
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+
+/// Program-wide settings, which lifts can override.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Settings {
+    /// How lifts (and reference weights) progress, if they don't say otherwise.
+    pub progression: Option<ProgressionScheme>,
+    /// What to do once a weight stops going up. Ten percent off after three stalls, if not given.
+    pub deload: Option<DeloadRule>,
+    /// How weights are rounded, if lifts don't say otherwise. Up to the next 5 lb (or 2.5 kg),
+    /// if not given.
+    pub rounding: Option<Rounding>,
+    /// What weights are measured in. Pounds, if not given.
+    pub unit: Option<Unit>,
+    /// What the empty barbell weighs. 45 lb (or 20 kg), if not given.
+    pub bar: Option<f64>,
+    /// The plates there are to load the bar with. As many of the usual plates as needed,
+    /// if not given.
+    pub plates: Option<Vec<Plates>>,
+    /// How lifts based on a reference weight are warmed up for. Not at all, if not given.
+    pub warmup: Option<WarmupScheme>,
+}
+
+impl Display for Settings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut entries: Vec<String> = Vec::new();
+        if let Some(progression) = &self.progression {
+            entries.push(format!("progression = {progression}"));
+        }
+        if let Some(deload) = &self.deload {
+            entries.push(format!("deload = {deload}"));
+        }
+        if let Some(rounding) = &self.rounding {
+            entries.push(format!("rounding = {rounding}"));
+        }
+        if let Some(unit) = &self.unit {
+            entries.push(format!("unit = {unit}"));
+        }
+        if let Some(bar) = &self.bar {
+            entries.push(format!("bar = {bar}"));
+        }
+        if let Some(plates) = &self.plates {
+            entries.push(format!("plates = {}", Plates::display_all(plates)));
+        }
+        if let Some(warmup) = &self.warmup {
+            entries.push(format!("warmup = {warmup}"));
+        }
+        write!(f, "{}", entries.join(" !! "))
+    }
+}
+
+/// The settings there are, as they're written in notation.
+const SETTINGS: [&str; 7] = ["progression", "deload", "rounding", "unit", "bar", "plates", "warmup"];
+
+impl Settings {
+    /// Notation is a list of `key = value` entries, separated by ` !! `:
+    /// ```
+    /// # use yawa::domain::settings::Settings;
+    /// Settings::parse("progression = linear").unwrap();
+    /// Settings::parse("progression = linear !! deload = 15% after 2").unwrap();
+    /// Settings::parse("unit = kg !! bar = 15 !! plates = 20x4 10 5 2.5 1.25").unwrap();
+    /// Settings::parse("").unwrap();
+    /// Settings::parse("colour = blue").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        let mut settings = Settings::default();
+        notation
+            .split(" !! ")
+            .filter(|entry| !entry.trim().is_empty())
+            .try_for_each(|entry| -> Result<()> {
+                let (key, value) = entry
+                    .split_once('=')
+                    .ok_or(NotationError::new("Cannot parse setting", entry))?;
+                let key = key.trim();
+                let error = format!("Cannot parse {key}");
+                match key {
+                    "progression" => {
+                        settings.progression =
+                            Some(ProgressionScheme::parse(value.trim()).notation(&error, value)?)
+                    }
+                    "deload" => {
+                        settings.deload = Some(DeloadRule::parse(value.trim()).notation(&error, value)?)
+                    }
+                    "rounding" => {
+                        settings.rounding = Some(Rounding::parse(value.trim()).notation(&error, value)?)
+                    }
+                    "unit" => settings.unit = Some(Unit::parse(value.trim()).notation(&error, value)?),
+                    "bar" => settings.bar = Some(parse_bar(value.trim()).notation(&error, value)?),
+                    "plates" => settings.plates = Some(Plates::parse_all(value).notation(&error, value)?),
+                    "warmup" => {
+                        settings.warmup = Some(WarmupScheme::parse(value).notation(&error, value)?)
+                    }
+                    other => {
+                        return Err(NotationError::new("Unknown setting", other)
+                            .suggesting(suggest(other, &SETTINGS).map(String::from))
+                            .into())
+                    }
+                }
+                Ok(())
+            })?;
+        Ok(settings)
+    }
+
+    /// The bar and plates barbell lifts are loaded with.
+    pub fn plate_inventory(&self) -> PlateInventory {
+        let standard = PlateInventory::standard(self.unit.unwrap_or_default());
+        PlateInventory {
+            bar: self.bar.unwrap_or(standard.bar),
+            plates: self.plates.clone().unwrap_or(standard.plates),
+            ..standard
+        }
+    }
+}
+
+fn parse_bar(notation: &str) -> Result<f64> {
+    let bar: f64 = notation.parse()?;
+    if bar.is_nan() || bar < 0.0 {
+        return Err(anyhow!("The bar can't weigh less than nothing: {notation}"));
+    }
+    Ok(bar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_and_display_settings() {
+        assert_eq!(Settings::parse("").unwrap(), Settings::default());
+        assert_eq!(Settings::default().to_string(), "");
+        let settings = Settings::parse("progression = linear+10").unwrap();
+        assert_eq!(
+            settings.progression,
+            Some(ProgressionScheme::Linear {
+                increment: Some(10.0)
+            })
+        );
+        assert_eq!(settings.to_string(), "progression = linear+10");
+        let settings = Settings::parse("deload = never !! progression = double").unwrap();
+        assert_eq!(settings.deload, Some(DeloadRule::Never));
+        assert_eq!(settings.to_string(), "progression = double !! deload = never");
+        let settings = Settings::parse("rounding = 2.5 nearest").unwrap();
+        assert_eq!(settings.to_string(), "rounding = 2.5 nearest");
+        let settings = Settings::parse("unit = kg").unwrap();
+        assert_eq!(settings.unit, Some(Unit::Kg));
+        let settings = Settings::parse("bar = 35 !! plates = 45x4 25 10 5").unwrap();
+        assert_eq!(settings.bar, Some(35.0));
+        assert_eq!(settings.to_string(), "bar = 35 !! plates = 45x4 25 10 5");
+        assert!(Settings::parse("bar = -10").is_err());
+        let settings = Settings::parse("warmup = none").unwrap();
+        assert_eq!(settings.warmup, Some(WarmupScheme { steps: vec![] }));
+    }
+}
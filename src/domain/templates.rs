@@ -0,0 +1,173 @@
+use crate::domain::programs::NewProgram;
+use anyhow::{anyhow, Result};
+
+/// A named, versioned program that comes with yawa, written in the notation read by
+/// `NewProgram::parse()`. Its version goes up whenever it changes, so a program started from
+/// an older version can be told apart (see `changed_since()`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgramTemplate {
+    pub name: &'static str,
+    pub version: usize,
+    pub description: &'static str,
+    pub notation: &'static str,
+}
+
+impl ProgramTemplate {
+    pub fn new_program(&self) -> NewProgram {
+        NewProgram::parse(self.notation).expect("Built-in program templates should parse")
+    }
+}
+
+/// ```
+/// # use yawa::domain::templates;
+/// templates::find("gzcl-4day").unwrap();
+/// templates::find("not-a-program").is_err();
+/// ```
+pub fn find(name: &str) -> Result<ProgramTemplate> {
+    all()
+        .into_iter()
+        .find(|template| template.name == name)
+        .ok_or(anyhow!(
+            "No program template named '{name}'. Run 'programs list' to see them all."
+        ))
+}
+
+/// The template, if it has changed since the version a program was started from.
+/// ```
+/// # use yawa::domain::templates;
+/// assert!(templates::changed_since("gzcl-4day", 1).is_none());
+/// assert_eq!(templates::changed_since("gzcl-4day", 0).unwrap().version, 1);
+/// assert!(templates::changed_since("not-a-program", 0).is_none());
+/// ```
+pub fn changed_since(name: &str, version: usize) -> Option<ProgramTemplate> {
+    find(name).ok().filter(|template| template.version != version)
+}
+
+pub fn all() -> Vec<ProgramTemplate> {
+    vec![
+        ProgramTemplate {
+            name: "gzcl-4day",
+            version: 1,
+            description: "The default program. Four days of T1/T2/T3 work, split by movement.",
+            notation: "GZCL-based 4-day cycle (Pull, Push, Legs, Core) !!! 45 !!!
+                Pull | Weighted Pullup -> 4x3,1x3+ @ 0.5r-30 equipment bodyweight | Pullup -> 3x7+ equipment bodyweight | Barbell Row -> 3x10 @ 0.65r | Face Pull -> 2x15,1x15-25 @ add20 equipment machine | Cable Curl -> 2x15,1x15-25 @ add20 equipment machine !!
+                Push | Bench press -> 4x3,1x3+ @ 1r | Overhead press -> 3x10 @ 0.5r | Incline bench press -> 3x10 @ 0.6r | Pushup -> 3x15+ equipment bodyweight | Tricep Cable Pressdown -> 2x15,1x15-25 @ add20 equipment machine !!
+                Legs | Squat -> 4x3,1x3+ @ 1.35r | Deadlift -> 3x8 @ 1.25r | Romanian Deadlift -> 3x10 @ 0.675r | Leg press -> 2x15,1x15-25 @ add30 equipment machine | Standing dumbbell calf raise -> 2x15,1x15-25 @ add20 equipment dumbbell !!
+                Core | Plank -> 1x30s @ any | Ab Rollout -> 3xAny | Cable Core Press -> 3xAny @ any | Bent-knee reverse hyperextension -> 3xAny @ any | Knee raises -> 3xAny | Leg extensions -> 3xAny @ any
+                !!! Face Pull = 30 !! Cable Curl = 20 !! Tricep Cable Pressdown = 20 !! Leg press = 45 !! Standing dumbbell calf raise = 45",
+        },
+        ProgramTemplate {
+            name: "gzcl-4day-stages",
+            version: 1,
+            description: "The default program, with each T1 lift on its own reference weight, going through GZCLP's stages when it fails.",
+            notation: "GZCL-based 4-day cycle with T1 stages (Pull, Push, Legs, Core) !!! 45, pullup = 0.5r-30, bench = 1r, squat = 1.35r !!!
+                Pull | Weighted Pullup -> 4x3,1x3+ @ 1r[pullup] progress gzclp-t1 equipment bodyweight | Pullup -> 3x7+ equipment bodyweight | Barbell Row -> 3x10 @ 0.65r | Face Pull -> 2x15,1x15-25 @ add20 equipment machine | Cable Curl -> 2x15,1x15-25 @ add20 equipment machine !!
+                Push | Bench press -> 4x3,1x3+ @ 1r[bench] progress gzclp-t1 | Overhead press -> 3x10 @ 0.5r | Incline bench press -> 3x10 @ 0.6r | Pushup -> 3x15+ equipment bodyweight | Tricep Cable Pressdown -> 2x15,1x15-25 @ add20 equipment machine !!
+                Legs | Squat -> 4x3,1x3+ @ 1r[squat] progress gzclp-t1+10 | Deadlift -> 3x8 @ 1.25r | Romanian Deadlift -> 3x10 @ 0.675r | Leg press -> 2x15,1x15-25 @ add30 equipment machine | Standing dumbbell calf raise -> 2x15,1x15-25 @ add20 equipment dumbbell !!
+                Core | Plank -> 1x30s @ any | Ab Rollout -> 3xAny | Cable Core Press -> 3xAny @ any | Bent-knee reverse hyperextension -> 3xAny @ any | Knee raises -> 3xAny | Leg extensions -> 3xAny @ any
+                !!! Face Pull = 30 !! Cable Curl = 20 !! Tricep Cable Pressdown = 20 !! Leg press = 45 !! Standing dumbbell calf raise = 45",
+        },
+        ProgramTemplate {
+            name: "gzclp-3day",
+            version: 1,
+            description: "GZCLP, rotating through four workouts (A1, B1, A2, B2) over three days a week.",
+            notation: "GZCLP 3-day (A1, B1, A2, B2) !!! 45, squat = 1.35r, bench = 1r, press = 0.65r, deadlift = 1.6r, squat t2 = 0.9r, bench t2 = 0.65r, press t2 = 0.4r, deadlift t2 = 1.05r !!!
+                A1 | Squat -> 4x3,1x3+ @ 1r[squat] progress gzclp-t1+10 | Bench press -> 3x10 @ 1r[bench t2] progress gzclp-t2 | Lat pulldown -> 2x15,1x15-25 @ add10 progress gzclp-t3 equipment machine !!
+                B1 | Overhead press -> 4x3,1x3+ @ 1r[press] progress gzclp-t1 | Deadlift -> 3x10 @ 1r[deadlift t2] progress gzclp-t2+10 | Dumbbell row -> 2x15,1x15-25 @ add5 progress gzclp-t3 equipment dumbbell !!
+                A2 | Bench press -> 4x3,1x3+ @ 1r[bench] progress gzclp-t1 | Squat -> 3x10 @ 1r[squat t2] progress gzclp-t2+10 | Lat pulldown -> 2x15,1x15-25 @ add10 progress gzclp-t3 equipment machine !!
+                B2 | Deadlift -> 4x3,1x3+ @ 1r[deadlift] progress gzclp-t1+10 | Overhead press -> 3x10 @ 1r[press t2] progress gzclp-t2 | Dumbbell row -> 2x15,1x15-25 @ add5 progress gzclp-t3 equipment dumbbell
+                !!! Lat pulldown = 50 !! Dumbbell row = 25",
+        },
+        ProgramTemplate {
+            name: "531-bbb",
+            version: 1,
+            description: "5/3/1 Boring But Big: a four week wave on the main lift, then 5x10 of it at half weight.",
+            notation: "5/3/1 Boring But Big (Press, Deadlift, Bench, Squat) !!! 45, press = 0.55r, deadlift = 1.35r, bench = 0.85r, squat = 1.15r !!!
+                Press | Overhead press -> 3x5+ @ 1r[press] progress 531 | Overhead press (BBB) -> 5x10 @ 0.5r[press] | Chin-up -> 5x10 equipment bodyweight !!
+                Deadlift | Deadlift -> 3x5+ @ 1r[deadlift] progress 531 | Deadlift (BBB) -> 5x10 @ 0.5r[deadlift] | Hanging leg raise -> 5x15 equipment bodyweight !!
+                Bench | Bench press -> 3x5+ @ 1r[bench] progress 531 | Bench press (BBB) -> 5x10 @ 0.5r[bench] | Dumbbell row -> 5x10 @ add5 equipment dumbbell !!
+                Squat | Squat -> 3x5+ @ 1r[squat] progress 531 | Squat (BBB) -> 5x10 @ 0.5r[squat] | Leg curl -> 5x10 @ add5 equipment machine
+                !!! Dumbbell row = 25 !! Leg curl = 40",
+        },
+        ProgramTemplate {
+            name: "starting-strength",
+            version: 1,
+            description: "Starting Strength: alternate workouts A and B, adding weight to every lift after each pair of them.",
+            notation: "Starting Strength (A, B) !!! 45, squat = 1.35r, bench = 1r, deadlift = 1.6r, press = 0.65r, clean = 0.8r !!!
+                A | Squat -> 3x5 @ 1r[squat] | Bench press -> 3x5 @ 1r[bench] | Deadlift -> 1x5 @ 1r[deadlift] !!
+                B | Squat -> 3x5 @ 1r[squat] | Overhead press -> 3x5 @ 1r[press] | Power clean -> 5x3 @ 1r[clean]",
+        },
+        ProgramTemplate {
+            name: "ppl-6day",
+            version: 1,
+            description: "Push/Pull/Legs twice a week, with a heavy compound lift to start each day.",
+            notation: "Push/Pull/Legs 6-day !!! 45, deadlift = 1.6r, bench = 1r, squat = 1.35r, row = 0.65r, press = 0.65r !!!
+                Pull A | Deadlift -> 1x5+ @ 1r[deadlift] | Lat pulldown -> 3x8-12 @ add5 equipment machine | Seated cable row -> 3x8-12 @ add5 equipment machine | Face Pull -> 5x15-20 @ add5 equipment machine | Dumbbell curl -> 4x8-12 @ add5 equipment dumbbell !!
+                Push A | Bench press -> 4x5,1x5+ @ 1r[bench] | Overhead press -> 3x8-12 @ 0.7r[press] | Incline dumbbell press -> 3x8-12 @ add5 equipment dumbbell | Triceps pushdown -> 3x8-12 @ add5 equipment machine | Lateral raise -> 3x15-20 @ add5 equipment dumbbell !!
+                Legs A | Squat -> 2x5,1x5+ @ 1r[squat] | Romanian Deadlift -> 3x8-12 @ 0.5r[squat] | Leg press -> 3x8-12 @ add10 equipment machine | Leg curl -> 3x8-12 @ add5 equipment machine | Standing calf raise -> 5x8-12 @ add5 equipment machine !!
+                Pull B | Barbell Row -> 4x5,1x5+ @ 1r[row] | Pullup -> 3x8+ equipment bodyweight | Seated cable row -> 3x8-12 @ add5 equipment machine | Face Pull -> 5x15-20 @ add5 equipment machine | Hammer curl -> 4x8-12 @ add5 equipment dumbbell !!
+                Push B | Overhead press -> 4x5,1x5+ @ 1r[press] | Bench press -> 3x8-12 @ 0.7r[bench] | Incline dumbbell press -> 3x8-12 @ add5 equipment dumbbell | Triceps pushdown -> 3x8-12 @ add5 equipment machine | Lateral raise -> 3x15-20 @ add5 equipment dumbbell !!
+                Legs B | Squat -> 2x5,1x5+ @ 1r[squat] | Romanian Deadlift -> 3x8-12 @ 0.5r[squat] | Leg press -> 3x8-12 @ add10 equipment machine | Leg curl -> 3x8-12 @ add5 equipment machine | Standing calf raise -> 5x8-12 @ add5 equipment machine
+                !!! Lat pulldown = 50 !! Seated cable row = 50 !! Face Pull = 20 !! Dumbbell curl = 15 !! Incline dumbbell press = 30 !! Triceps pushdown = 20 !! Lateral raise = 10 !! Leg press = 90 !! Leg curl = 40 !! Standing calf raise = 45 !! Hammer curl = 15",
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_templates_parse_every_day() {
+        let day_names = |name: &str| -> Vec<String> {
+            find(name)
+                .unwrap()
+                .new_program()
+                .days
+                .iter()
+                .map(|day| day.name.clone())
+                .collect()
+        };
+        assert_eq!(day_names("gzcl-4day"), ["Pull", "Push", "Legs", "Core"]);
+        assert_eq!(day_names("gzcl-4day-stages"), ["Pull", "Push", "Legs", "Core"]);
+        assert_eq!(day_names("gzclp-3day"), ["A1", "B1", "A2", "B2"]);
+        assert_eq!(day_names("531-bbb"), ["Press", "Deadlift", "Bench", "Squat"]);
+        assert_eq!(day_names("starting-strength"), ["A", "B"]);
+        assert_eq!(
+            day_names("ppl-6day"),
+            ["Pull A", "Push A", "Legs A", "Pull B", "Push B", "Legs B"]
+        );
+    }
+
+    #[test]
+    fn template_names_are_unique() {
+        let templates = all();
+        templates.iter().for_each(|template| {
+            assert_eq!(
+                templates.iter().filter(|it| it.name == template.name).count(),
+                1
+            );
+        });
+    }
+
+    #[test]
+    fn all_non_reference_weights_initialized() {
+        use crate::domain::weight_scheme::WeightScheme;
+        all().iter().for_each(|template| {
+            let new_program = template.new_program();
+            new_program
+                .days
+                .iter()
+                .flat_map(|day| day.lifts.iter())
+                .filter(|lift| matches!(lift.weight, WeightScheme::LinearBasedOnPrevious { .. }))
+                .for_each(|lift| {
+                    assert!(
+                        new_program.weights.contains_key(&lift.id()),
+                        "{} has no starting weight for {}",
+                        template.name,
+                        lift
+                    )
+                });
+        });
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// How long to rest between sets of a lift.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rest {
+    pub duration: Duration,
+}
+
+impl Display for Rest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", display_duration(self.duration))
+    }
+}
+
+impl Rest {
+    /// Notation is minutes, seconds, or both (see `parse_duration()`):
+    /// ```
+    /// # use yawa::domain::timing::Rest;
+    /// Rest::parse("3min").unwrap();
+    /// Rest::parse("90s").unwrap();
+    /// Rest::parse("2min30s").unwrap();
+    /// Rest::parse("3").is_err();
+    /// Rest::parse("a while").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        Ok(Rest {
+            duration: parse_duration(notation)?,
+        })
+    }
+}
+
+/// Notation is minutes, seconds, or both, like '3min', '90s' or '2min30s'. Minutes are
+/// never just 'm', which is meters:
+/// ```
+/// # use yawa::domain::timing::parse_duration;
+/// parse_duration("2min30s").unwrap();
+/// parse_duration("2.5min").is_err();
+/// parse_duration("3m").is_err();
+/// ```
+pub fn parse_duration(notation: &str) -> Result<Duration> {
+    let notation = notation.trim();
+    let error =
+        || anyhow!("Cannot parse time: {notation}. Use minutes and seconds, like '2min30s'.");
+    let (minutes, seconds) = match notation.split_once("min") {
+        Some((minutes, seconds)) => (minutes, seconds),
+        None => ("0", notation),
+    };
+    let minutes: u64 = minutes.parse().map_err(|_| error())?;
+    let seconds: u64 = match seconds {
+        "" if notation.ends_with("min") => 0,
+        seconds => seconds
+            .strip_suffix('s')
+            .ok_or_else(error)?
+            .parse()
+            .map_err(|_| error())?,
+    };
+    Ok(Duration::from_secs(minutes * 60 + seconds))
+}
+
+/// Like '3min', '45s' or '2min30s', the way `parse_duration()` reads it.
+pub fn display_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 60, seconds % 60) {
+        (0, seconds) => format!("{seconds}s"),
+        (minutes, 0) => format!("{minutes}min"),
+        (minutes, seconds) => format!("{minutes}min{seconds}s"),
+    }
+}
+
+/// How many seconds each part of a rep takes: lowering the weight, pausing at the bottom,
+/// lifting it, then pausing at the top. `None` is as fast as possible, written 'X'.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tempo {
+    pub phases: [Option<u64>; 4],
+}
+
+impl Display for Tempo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.phases.iter().try_for_each(|phase| match phase {
+            Some(seconds) => write!(f, "{seconds}"),
+            None => write!(f, "X"),
+        })
+    }
+}
+
+impl Tempo {
+    /// Notation is one digit for each part of the rep, or 'X' for as fast as possible:
+    /// ```
+    /// # use yawa::domain::timing::Tempo;
+    /// Tempo::parse("3010").unwrap();
+    /// Tempo::parse("20X0").unwrap();
+    /// Tempo::parse("301").is_err();
+    /// Tempo::parse("3-0-1-0").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        let notation = notation.trim();
+        let error = || anyhow!("Cannot parse tempo: {notation}. Use four digits, like '3010'.");
+        let phases = notation
+            .chars()
+            .map(|phase| match phase {
+                'X' | 'x' => Ok(None),
+                digit => digit.to_digit(10).map(|it| Some(it as u64)).ok_or_else(error),
+            })
+            .collect::<Result<Vec<Option<u64>>>>()?;
+        Ok(Tempo {
+            phases: phases.try_into().map_err(|_| error())?,
+        })
+    }
+
+    /// How long a set of this many reps keeps the muscles under tension, counting fast
+    /// parts of the rep as taking no time.
+    pub fn time_under_tension(&self, reps: usize) -> Duration {
+        let per_rep: u64 = self.phases.iter().flatten().sum();
+        Duration::from_secs(per_rep * reps as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_and_display() {
+        ["3min", "90s", "2min30s", "45s"].iter().for_each(|notation| {
+            let rest = Rest::parse(notation).unwrap();
+            assert_eq!(Rest::parse(&rest.to_string()).unwrap(), rest);
+        });
+        assert_eq!(Rest::parse("90s").unwrap().to_string(), "1min30s");
+        assert_eq!(Rest::parse("3min").unwrap().duration, Duration::from_secs(180));
+        assert!(Rest::parse("3m").is_err());
+        ["3010", "20X0"].iter().for_each(|notation| {
+            assert_eq!(Tempo::parse(notation).unwrap().to_string(), *notation)
+        });
+    }
+
+    #[test]
+    fn counts_time_under_tension() {
+        assert_eq!(
+            Tempo::parse("3010").unwrap().time_under_tension(5),
+            Duration::from_secs(20)
+        );
+        assert_eq!(
+            Tempo::parse("21X1").unwrap().time_under_tension(3),
+            Duration::from_secs(12)
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use crate::domain::rounding::{Rounding, RoundingDirection};
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+
+const POUNDS_PER_KILOGRAM: f64 = 2.20462262;
+
+/// What every weight of a program is measured in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Unit {
+    #[default]
+    Lb,
+    Kg,
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unit::Lb => write!(f, "lb"),
+            Unit::Kg => write!(f, "kg"),
+        }
+    }
+}
+
+impl Unit {
+    /// ```
+    /// # use yawa::domain::units::Unit;
+    /// Unit::parse("kg").unwrap();
+    /// Unit::parse("lbs").unwrap();
+    /// Unit::parse("stone").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        match notation.trim() {
+            "lb" | "lbs" => Ok(Unit::Lb),
+            "kg" | "kgs" => Ok(Unit::Kg),
+            other => Err(anyhow!("Unknown unit: {other}. Use 'lb' or 'kg'.")),
+        }
+    }
+
+    /// The same weight, measured in another unit.
+    pub fn convert(&self, weight: f64, to: Unit) -> f64 {
+        match (self, to) {
+            (Unit::Lb, Unit::Kg) => weight / POUNDS_PER_KILOGRAM,
+            (Unit::Kg, Unit::Lb) => weight * POUNDS_PER_KILOGRAM,
+            _ => weight,
+        }
+    }
+
+    /// The smallest jump in weight with a barbell and the usual plates: two of the smallest plate.
+    pub fn increment(&self) -> f64 {
+        match self {
+            Unit::Lb => 5.0,
+            Unit::Kg => 2.5,
+        }
+    }
+
+    /// How weights are rounded when nothing says otherwise.
+    pub fn default_rounding(&self) -> Rounding {
+        Rounding {
+            increment: self.increment(),
+            direction: RoundingDirection::Up,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_units() {
+        assert_eq!(Unit::Lb.convert(225.0, Unit::Lb), 225.0);
+        assert!((Unit::Lb.convert(220.462262, Unit::Kg) - 100.0).abs() < 1e-6);
+        assert!((Unit::Kg.convert(100.0, Unit::Lb) - 220.462262).abs() < 1e-6);
+        assert_eq!(Unit::Kg.default_rounding().round(61.0), 62.5);
+    }
+}
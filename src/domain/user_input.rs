@@ -1,8 +1,40 @@
+use crate::domain::units::Unit;
+use std::path::PathBuf;
+
 pub enum Action {
     StartProgram {
-        reference_weight: usize
+        reference_weight: Option<f64>,
+        named_reference_weights: Vec<(String, f64)>,
+        source: ProgramSource,
+    },
+    ListPrograms,
+    ShowProgram {
+        name: String,
     },
     SeeStatus,
     SeeNextDay,
-    CompleteDay
+    CompleteDay,
+    ConvertUnits {
+        unit: Unit,
+    },
+    ShowPlates {
+        weight: f64,
+    },
+    LogBodyweight {
+        weight: f64,
+    },
+    LintProgram {
+        path: PathBuf,
+    },
+    FormatProgram {
+        path: PathBuf,
+        /// Only check whether the file is formatted, without changing it.
+        check: bool,
+    },
+}
+
+pub enum ProgramSource {
+    Default,
+    File(PathBuf),
+    Template(String),
 }
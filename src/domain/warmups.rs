@@ -0,0 +1,185 @@
+use crate::domain::units::Unit;
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+
+/// How to ramp up to a lift's working weight, one warm-up set per step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WarmupScheme {
+    pub steps: Vec<WarmupStep>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WarmupStep {
+    /// Just the bar. Skipped for lifts that aren't done with one.
+    EmptyBar { reps: usize },
+    /// A percentage of the working weight.
+    Percent { percent: f64, reps: usize },
+}
+
+/// One set to do before a lift's working sets, which doesn't count towards its progression.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WarmupSet {
+    pub weight: f64,
+    pub reps: usize,
+    pub unit: Unit,
+}
+
+impl Display for WarmupStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WarmupStep::EmptyBar { reps } => write!(f, "bar x{reps}"),
+            WarmupStep::Percent { percent, reps } => write!(f, "{percent}% x{reps}"),
+        }
+    }
+}
+
+impl Display for WarmupScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.steps.is_empty() {
+            return write!(f, "none");
+        }
+        write!(
+            f,
+            "{}",
+            self.steps
+                .iter()
+                .map(|step| step.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+impl Display for WarmupSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} @ {} {}", self.reps, self.weight, self.unit)
+    }
+}
+
+impl WarmupSet {
+    pub fn display_all(sets: &[WarmupSet]) -> String {
+        sets.iter()
+            .map(|set| set.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+impl WarmupScheme {
+    /// Notation is a list of steps, each the empty bar or a percentage of the working weight,
+    /// with its reps. Or 'none', for no warm-up:
+    /// ```
+    /// # use yawa::domain::warmups::WarmupScheme;
+    /// WarmupScheme::parse("bar x10, 40% x5, 60% x3, 80% x2").unwrap();
+    /// WarmupScheme::parse("50% x5, 75% x3").unwrap();
+    /// WarmupScheme::parse("none").unwrap();
+    /// WarmupScheme::parse("50%").is_err();
+    /// WarmupScheme::parse("half x5").is_err();
+    /// WarmupScheme::parse("bar x0").is_err();
+    /// WarmupScheme::parse("120% x1").is_err();
+    /// ```
+    pub fn parse(notation: &str) -> Result<Self> {
+        if notation.trim() == "none" {
+            return Ok(WarmupScheme { steps: Vec::new() });
+        }
+        let steps = notation
+            .split(',')
+            .map(|step| -> Result<WarmupStep> {
+                let error = || anyhow!("Cannot parse warm-up step: {}", step.trim());
+                let (load, reps) = step.trim().split_once(" x").ok_or_else(error)?;
+                let reps: usize = reps.trim().parse().map_err(|_| error())?;
+                if reps == 0 {
+                    return Err(anyhow!("A warm-up step needs some reps: {}", step.trim()));
+                }
+                match load.trim() {
+                    "bar" => Ok(WarmupStep::EmptyBar { reps }),
+                    load => {
+                        let percent: f64 = load
+                            .strip_suffix('%')
+                            .ok_or_else(error)?
+                            .parse()
+                            .map_err(|_| error())?;
+                        if !(percent > 0.0 && percent <= 100.0) {
+                            return Err(anyhow!(
+                                "A warm-up step is from above 0% up to 100% of the working weight: {}",
+                                step.trim()
+                            ));
+                        }
+                        Ok(WarmupStep::Percent { percent, reps })
+                    }
+                }
+            })
+            .collect::<Result<Vec<WarmupStep>>>()?;
+        Ok(WarmupScheme { steps })
+    }
+
+    /// The warm-up sets leading to the working weight, lightest first. Weights are moved onto
+    /// ones that can be loaded with the function given. Steps that wouldn't be lighter than the working weight, or
+    /// heavier than the step before them, are left out.
+    pub fn ladder(
+        &self,
+        working_weight: f64,
+        bar: Option<f64>,
+        loadable: impl Fn(f64) -> f64,
+        unit: Unit,
+    ) -> Vec<WarmupSet> {
+        let mut sets: Vec<WarmupSet> = Vec::new();
+        self.steps.iter().for_each(|step| {
+            let (weight, reps) = match step {
+                WarmupStep::EmptyBar { reps } => match bar {
+                    Some(bar) => (bar, *reps),
+                    None => return,
+                },
+                WarmupStep::Percent { percent, reps } => (
+                    loadable(working_weight * percent / 100.0).max(bar.unwrap_or(0.0)),
+                    *reps,
+                ),
+            };
+            let heavier = sets.last().is_none_or(|last| weight > last.weight);
+            if weight > 0.0 && weight < working_weight && heavier {
+                sets.push(WarmupSet { weight, reps, unit });
+            }
+        });
+        sets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::rounding::Rounding;
+
+    #[test]
+    fn can_parse_and_display() {
+        ["bar x10, 40% x5, 60% x3, 80% x2", "50% x5, 72.5% x3", "none"]
+            .iter()
+            .for_each(|notation| {
+                assert_eq!(WarmupScheme::parse(notation).unwrap().to_string(), *notation)
+            });
+    }
+
+    fn standard() -> WarmupScheme {
+        WarmupScheme::parse("bar x10, 40% x5, 60% x3, 80% x2").unwrap()
+    }
+
+    #[test]
+    fn ramps_up_to_the_working_weight() {
+        let weights = |working_weight: f64, bar: Option<f64>| -> Vec<f64> {
+            standard()
+                .ladder(working_weight, bar, |it| Rounding::default().down().round(it), Unit::Lb)
+                .iter()
+                .map(|set| set.weight)
+                .collect()
+        };
+        assert_eq!(weights(315.0, Some(45.0)), vec![45.0, 125.0, 185.0, 250.0]);
+        assert_eq!(weights(95.0, Some(45.0)), vec![45.0, 55.0, 75.0]);
+        assert_eq!(weights(45.0, Some(45.0)), Vec::<f64>::new());
+        assert_eq!(weights(50.0, None), vec![20.0, 30.0, 40.0]);
+        assert_eq!(
+            standard()
+                .ladder(315.0, Some(45.0), |it| it, Unit::Lb)[0]
+                .to_string(),
+            "10 @ 45 lb"
+        );
+    }
+}
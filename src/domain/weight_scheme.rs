@@ -1,24 +1,45 @@
 use std::fmt::{Display, Formatter};
 use anyhow::anyhow;
+use crate::domain::notation::{
+    decimal, offset, quote, split_once_unquoted, suggest, unquote, NotationError,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum WeightScheme {
-    BasedOnReference { multiplier: f64, offset: i64 },
+    BasedOnReference {
+        multiplier: f64,
+        offset: f64,
+        /// The name of the reference weight to use, if not the program's main one.
+        reference: Option<String>,
+    },
     Any,
     None,
-    LinearBasedOnPrevious { amount_to_increase: usize },
+    LinearBasedOnPrevious { amount_to_increase: f64 },
+    /// Bodyweight, with weight added (or taken off, like on an assisted pullup machine).
+    /// The weight added stays as written: these lifts progress by their own progression,
+    /// like 'progress reps'. For a weight added that goes up, base the lift on a reference
+    /// weight with 'equipment bodyweight', like 'Weighted Pullup -> 3x5 @ 0.5r-30 equipment bodyweight'.
+    Bodyweight { added: f64 },
 }
 
 impl Display for WeightScheme {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         return match self {
-            WeightScheme::BasedOnReference { multiplier, offset } => {
-                if offset > &0 {
-                    write!(f, "{multiplier}r+{offset}")
-                } else if offset == &0 {
-                    write!(f, "{multiplier}r")
+            WeightScheme::BasedOnReference {
+                multiplier,
+                offset,
+                reference,
+            } => {
+                write!(f, "{multiplier}r")?;
+                if let Some(reference) = reference {
+                    write!(f, "[{}]", quote(reference, &REFERENCE_SEPARATORS))?;
+                }
+                if *offset > 0.0 {
+                    write!(f, "+{offset}")
+                } else if *offset == 0.0 {
+                    Ok(())
                 } else {
-                    write!(f, "{multiplier}r{offset}")
+                    write!(f, "{offset}")
                 }
             }
             WeightScheme::Any => write!(f, "any"),
@@ -26,73 +47,221 @@ impl Display for WeightScheme {
             WeightScheme::LinearBasedOnPrevious { amount_to_increase } => {
                 write!(f, "add{amount_to_increase}")
             }
+            WeightScheme::Bodyweight { added } => write!(f, "{}", bodyweight_plus(*added)),
         };
     }
 }
 
+/// What a reference weight's name can't have in it without being put in quotes.
+pub const REFERENCE_SEPARATORS: [&str; 10] =
+    ["]", ",", "=", "@", "/side", "/hand", " | ", " + ", " !! ", " !!! "];
+
+/// Like 'bw+20', 'bw-40', or just 'bw'.
+pub fn bodyweight_plus(added: f64) -> String {
+    if added > 0.0 {
+        format!("bw+{added}")
+    } else if added == 0.0 {
+        "bw".to_string()
+    } else {
+        format!("bw{added}")
+    }
+}
+
 impl WeightScheme {
+    /// The same scheme, for a fraction (or multiple) of the weight.
+    pub fn scaled(&self, factor: f64) -> Self {
+        match self {
+            WeightScheme::BasedOnReference {
+                multiplier,
+                offset,
+                reference,
+            } => WeightScheme::BasedOnReference {
+                multiplier: multiplier * factor,
+                offset: offset * factor,
+                reference: reference.clone(),
+            },
+            other => other.clone(),
+        }
+    }
+
     /// notation options:
     /// ```
     /// # use yawa::domain::weight_scheme::*;
     /// WeightScheme::parse("any").unwrap();
     /// WeightScheme::parse("3.14r+12").unwrap();
     /// WeightScheme::parse("3.14r-12").unwrap();
+    /// WeightScheme::parse("0.85r[squat]+10").unwrap();
+    /// WeightScheme::parse("0.85r[]").is_err();
     /// WeightScheme::parse("add10").unwrap();
+    /// WeightScheme::parse("add2.5").unwrap();
+    /// WeightScheme::parse("0.5r-2.5").unwrap();
+    /// WeightScheme::parse("bw").unwrap();
+    /// WeightScheme::parse("bw+20").unwrap();
+    /// WeightScheme::parse("bw-40").unwrap();
+    /// WeightScheme::parse("bw20").is_err();
     /// ```
     pub fn parse(notation: &str) -> anyhow::Result<Self> {
+        Self::parse_scheme(notation).map_err(|_| {
+            NotationError::new("Cannot parse weight", notation)
+                .suggesting(Self::fix(notation))
+                .into()
+        })
+    }
+
+    /// What the notation probably meant, if it parses once its case, its spacing,
+    /// or a typo in its keyword is fixed.
+    fn fix(notation: &str) -> Option<String> {
+        let notation = notation.to_lowercase().replace(' ', "");
+        let keyword: String = notation.chars().take_while(|it| it.is_alphabetic()).collect();
+        let number = &notation[keyword.len()..];
+        let trailing: String = notation.chars().rev().take_while(|it| it.is_alphabetic()).collect();
+        let trailing: String = trailing.chars().rev().collect();
+        [
+            Some(notation.clone()),
+            suggest(&keyword, &["add", "any", "bw"]).map(|keyword| match keyword {
+                "bw" if !number.starts_with(['+', '-']) => format!("bw+{number}"),
+                keyword => format!("{keyword}{number}"),
+            }),
+            suggest(&trailing, &["add"]).map(|keyword| {
+                format!("{keyword}{}", &notation[..notation.len() - trailing.len()])
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|fix| Self::parse_scheme(fix).is_ok())
+    }
+
+    fn parse_scheme(notation: &str) -> anyhow::Result<Self> {
         let error = "Invalid notation";
-        return if notation == "any" {
+        if notation == "any" {
             Ok(WeightScheme::Any)
-        } else if notation.contains('r') {
-            // 3.14r+12
-            let mut split = notation.split('r');
+        } else if let Some(added) = notation.strip_prefix("bw") {
+            // bw+20, or bw-40
+            Ok(WeightScheme::Bodyweight {
+                added: offset(added)?,
+            })
+        } else if let Some((multiplier, rest)) = notation.split_once('r') {
+            // 3.14r+12, or 3.14r[squat]+12
+            let (reference, rest) = match rest.strip_prefix('[') {
+                Some(rest) => {
+                    let (reference, rest) =
+                        split_once_unquoted(rest, "]").ok_or(anyhow!(error))?;
+                    let reference = unquote(reference)?;
+                    if reference.is_empty() {
+                        return Err(anyhow!(error));
+                    }
+                    (Some(reference), rest)
+                }
+                None => (None, rest),
+            };
             Ok(WeightScheme::BasedOnReference {
-                multiplier: split.next().ok_or(anyhow!(error))?.parse()?,
-                offset: split.next().unwrap_or("0").parse().unwrap_or(0),
+                multiplier: decimal(multiplier)?,
+                offset: offset(rest)?,
+                reference,
             })
-        } else {
+        } else if let Some(amount_to_increase) = notation.strip_prefix("add") {
             // add20
-            let add_str = notation.split("add").skip(1).next().ok_or(anyhow!(error))?;
             Ok(WeightScheme::LinearBasedOnPrevious {
-                amount_to_increase: add_str.parse()?,
+                amount_to_increase: decimal(amount_to_increase)?,
             })
-        };
+        } else {
+            Err(anyhow!(error))
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::domain::notation::NotationError;
     use crate::domain::weight_scheme::*;
 
     #[test]
+    fn suggests_fixes() {
+        let suggestion = |notation: &str| {
+            WeightScheme::parse(notation)
+                .unwrap_err()
+                .downcast::<NotationError>()
+                .unwrap()
+                .suggestion
+        };
+        assert_eq!(suggestion("ad20"), Some("add20".to_string()));
+        assert_eq!(suggestion("20add"), Some("add20".to_string()));
+        assert_eq!(suggestion("Add 5"), Some("add5".to_string()));
+        assert_eq!(suggestion("bw20"), Some("bw+20".to_string()));
+        assert_eq!(suggestion("ANY"), Some("any".to_string()));
+        assert_eq!(suggestion("heavy"), None);
+    }
+
+    #[test]
+    fn parses_strictly() {
+        ["1r progress linear", "1r10", "1r+", "1r+-5", "xadd5", "add+5", "infr", "bw+", "1r[squat]x", "1r[squat]+x"]
+            .iter()
+            .for_each(|notation| assert!(WeightScheme::parse(notation).is_err(), "{notation}"));
+        let reference = WeightScheme::BasedOnReference {
+            multiplier: 0.8,
+            offset: -10.0,
+            reference: Some("front squat, paused".to_string()),
+        };
+        assert_eq!(reference.to_string(), "0.8r[\"front squat, paused\"]-10");
+        ["any", "bw", "bw-40", "1.35r+12", "0.5r-2.5", "1r[squat]", "add2.5", "add-5"]
+            .iter()
+            .map(|notation| WeightScheme::parse(notation).unwrap())
+            .chain([reference])
+            .for_each(|scheme| {
+                assert_eq!(WeightScheme::parse(&scheme.to_string()).unwrap(), scheme)
+            });
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
     fn can_create_weight_schemes() {
         assert!(WeightScheme::parse(":(").is_err());
         assert_eq!(
             WeightScheme::parse("3.14r-12").unwrap(),
             WeightScheme::BasedOnReference {
                 multiplier: 3.14,
-                offset: -12
+                offset: -12.0,
+                reference: None
             }
         );
         assert_eq!(
             WeightScheme::parse("3.14r+12").unwrap(),
             WeightScheme::BasedOnReference {
                 multiplier: 3.14,
-                offset: 12
+                offset: 12.0,
+                reference: None
             }
         );
         assert_eq!(
             WeightScheme::parse("3.14r").unwrap(),
             WeightScheme::BasedOnReference {
                 multiplier: 3.14,
-                offset: 0
+                offset: 0.0,
+                reference: None
+            }
+        );
+        assert_eq!(
+            WeightScheme::parse("0.85r[squat]-10").unwrap(),
+            WeightScheme::BasedOnReference {
+                multiplier: 0.85,
+                offset: -10.0,
+                reference: Some("squat".to_string())
             }
         );
+        assert_eq!(
+            WeightScheme::parse("1r[front squat]").unwrap(),
+            WeightScheme::BasedOnReference {
+                multiplier: 1.0,
+                offset: 0.0,
+                reference: Some("front squat".to_string())
+            }
+        );
+        assert!(WeightScheme::parse("1r[squat").is_err());
         assert_eq!(WeightScheme::parse("any").unwrap(), WeightScheme::Any);
         assert_eq!(
             WeightScheme::parse("add20").unwrap(),
             WeightScheme::LinearBasedOnPrevious {
-                amount_to_increase: 20
+                amount_to_increase: 20.0
             }
         );
     }
@@ -111,10 +280,25 @@ mod tests {
             format!("{}", WeightScheme::parse("3.14r").unwrap()),
             "3.14r"
         );
+        assert_eq!(
+            format!("{}", WeightScheme::parse("0.85r[squat]+10").unwrap()),
+            "0.85r[squat]+10"
+        );
+        assert_eq!(
+            format!("{}", WeightScheme::parse("1r[squat]").unwrap()),
+            "1r[squat]"
+        );
         assert_eq!(format!("{}", WeightScheme::parse("any").unwrap()), "any");
         assert_eq!(
             format!("{}", WeightScheme::parse("add20").unwrap()),
             "add20"
         );
+        ["bw", "bw+20", "bw-40", "bw+2.5"].iter().for_each(|notation| {
+            assert_eq!(WeightScheme::parse(notation).unwrap().to_string(), *notation)
+        });
+        assert_eq!(
+            WeightScheme::parse("bw-40").unwrap(),
+            WeightScheme::Bodyweight { added: -40.0 }
+        );
     }
 }
\ No newline at end of file
@@ -8,6 +8,17 @@
 //!
 //! Compile this as a binary to run in your terminal.
 
+#![allow(
+    clippy::needless_return,
+    clippy::redundant_static_lifetimes,
+    clippy::needless_borrow,
+    clippy::needless_borrows_for_generic_args,
+    clippy::ptr_arg,
+    clippy::unit_arg,
+    clippy::needless_question_mark,
+    clippy::let_and_return
+)]
+
 /// Contains the code to orchestrate how the application works (controllers, services, adapters)
 pub mod application;
 
@@ -1,9 +1,10 @@
 use assert_cmd::assert::Assert;
 use assert_cmd::crate_name;
 use assert_cmd::Command;
+use predicates::prelude::*;
 use predicates::str::contains;
 use rand::random;
-use std::fs::{create_dir_all, remove_dir_all, File};
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write, File};
 use std::io::Read;
 use std::path::Path;
 
@@ -43,8 +44,8 @@ fn saves_in_desired_folder() {
         assert("-s in/a/nested/folder/ status", "", dir)
             .success()
             .stdout(contains("Current program: GZCL-based 4-day cycle"))
-            .stdout(contains("Current reference weight: 105\n"))
-            .stdout(contains("Starting reference weight: 105\n"))
+            .stdout(contains("Current reference weight: 105 lb\n"))
+            .stdout(contains("Starting reference weight: 105 lb\n"))
             .stdout(contains("Workouts completed: 0\n"));
         assert!(Path::new(&format!("{dir}/in/a/nested/folder/yawa_save_data/info.txt")).is_file());
     });
@@ -62,12 +63,316 @@ fn starts_program() {
         assert("status", "", dir)
             .success()
             .stdout(contains("Current program: GZCL-based 4-day cycle"))
-            .stdout(contains("Current reference weight: 105\n"))
-            .stdout(contains("Starting reference weight: 105\n"))
+            .stdout(contains("Current reference weight: 105 lb\n"))
+            .stdout(contains("Starting reference weight: 105 lb\n"))
             .stdout(contains("Workouts completed: 0\n"));
     });
 }
 
+#[test]
+fn starts_program_from_file() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Full body !!! 95 !!!\n\
+             A | Squat -> 3x5 @ 1.5r | Leg press -> 3x10 @ add20 !!\n\
+             B | Bench press -> 3x5 @ 1r\n\
+             !!! Leg press = 90\n",
+        )
+        .unwrap();
+        assert("start --program my_plan.txt -r 100", "", dir)
+            .success()
+            .stdout(contains("Started program: Full body"));
+        assert("status", "", dir)
+            .success()
+            .stdout(contains("Current reference weight: 100 lb\n"));
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Squat -> 3x5 @ 150 lb"))
+            .stdout(contains("Leg press -> 3x10 @ 90 lb"));
+    });
+}
+
+#[test]
+fn shows_every_problem_in_a_program() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Full body !!! 95 !!!\n\
+             A | Squat -> 3x5 @ 1r | Leg press -> 3x10 @ add20 !!\n\
+             B | Bench press -> 3x5 @ 1r | Bench press -> 3x5 @ 1r !!\n\
+             C | Squat -> 3x5 @ 1rr\n",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir)
+            .failure()
+            .stderr(contains("--> line 2"))
+            .stderr(contains("needs a starting weight"))
+            .stderr(contains("--> line 3"))
+            .stderr(contains("two lifts with the same name: Bench press"))
+            .stderr(contains("--> line 4"))
+            .stderr(contains("Found 3 problems."));
+    });
+}
+
+#[test]
+fn lints_program_files() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/good.txt"),
+            "Full body !!! 95 !!! A | Squat -> 3x5 @ 1r",
+        )
+        .unwrap();
+        write(
+            format!("{dir}/bad.txt"),
+            "Full body !!! 95 !!!\n\
+             A | Squat -> 3x5 @ 1r | Leg curl -> 3x15-10 @ add5 !!\n\
+             B | Plank -> 1x30s\n\
+             !!! Leg curl = 30\n",
+        )
+        .unwrap();
+        assert("lint good.txt", "", dir)
+            .success()
+            .stdout(contains("No problems found in good.txt"));
+        assert("lint bad.txt", "", dir)
+            .failure()
+            .stderr(contains("A rep range must go from fewer reps to more: 15-10"))
+            .stderr(contains("= did you mean '10-15'?"))
+            .stderr(contains("Nothing in this day progresses: B"))
+            .stderr(contains("Found 2 problems."));
+    });
+}
+
+#[test]
+fn formats_program_files() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        let path = format!("{dir}/my_plan.txt");
+        write(
+            &path,
+            "Full body !!! 95 !!! A | Squat -> 3x5,3x5 @ 1r   | Leg press -> 3x10 @ add20 progress linear equipment machine !! B | Bench press -> 3x5 @ 1r !!! Leg press = 90",
+        )
+        .unwrap();
+        assert("fmt --check my_plan.txt", "", dir)
+            .failure()
+            .stderr(contains("Not formatted: my_plan.txt"));
+        assert("fmt my_plan.txt", "", dir)
+            .success()
+            .stdout(contains("Formatted: my_plan.txt"));
+        let mut formatted = String::new();
+        File::open(&path)
+            .unwrap()
+            .read_to_string(&mut formatted)
+            .unwrap();
+        assert_eq!(
+            formatted,
+            "Full body !!! 95 !!!\n    \
+             A | Squat -> 6x5 @ 1r | Leg press -> 3x10 @ add20 progress linear equipment machine !!\n    \
+             B | Bench press -> 3x5 @ 1r\n\
+             !!! Leg press = 90\n"
+        );
+        assert("fmt --check my_plan.txt", "", dir)
+            .success()
+            .stdout(contains("Already formatted: my_plan.txt"));
+        assert("start -p my_plan.txt", "", dir).success();
+    });
+}
+
+#[test]
+fn starts_program_with_names_in_quotes() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "\"Push !!! Pull\" !!! 95 !!!\n\
+             \"A | B\" | \"Curl = press\" -> 3x10 @ add5 !!! \"Curl = press\" = 20\n",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir)
+            .success()
+            .stdout(contains("Started program: Push !!! Pull"));
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("=== Day: A | B ==="))
+            .stdout(contains("Curl = press -> 3x10 @ 20 lb"));
+    });
+}
+
+#[test]
+fn shows_where_a_program_is_wrong() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Full body !!! 95 !!!\n\
+             A | Squat -> 3x5 @ 1r | Leg press -> 3x10 @ ad20\n",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir)
+            .failure()
+            .stderr(contains("Cannot parse weight: ad20"))
+            .stderr(contains("--> line 2, column 45"))
+            .stderr(contains("= did you mean 'add20'?"));
+        assert("status", "", dir)
+            .failure()
+            .stderr(contains("Start a lifting program first!"));
+    });
+}
+
+#[test]
+fn starts_program_from_file_with_its_own_reference_weight() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Full body !!! 95 !!! A | Squat -> 3x5 @ 1r",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("status", "", dir)
+            .success()
+            .stdout(contains("Starting reference weight: 95 lb\n"));
+    });
+}
+
+#[test]
+fn works_in_kilograms_and_converts_units() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Full body !!! 60 !!! A | Squat -> 3x5 @ 1r | Press -> 3x5 @ 0.5r !!!  !!! unit = kg",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Squat -> 3x5 @ 60 kg\n    Plates: 20 kg bar + 20 per side\nPress -> 3x5 @ 30 kg"));
+        assert("convert-units lb", "", dir)
+            .success()
+            .stdout(contains("Converted program to lb"));
+        assert("status", "", dir)
+            .success()
+            .stdout(contains("Current reference weight: 130 lb\n"));
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Squat -> 3x5 @ 130 lb\n    Plates: 45 lb bar + 35, 5, 2.5 per side\nPress -> 3x5 @ 65 lb"));
+        assert("convert-units stone", "", dir).failure();
+    });
+}
+
+#[test]
+fn shows_warmups_when_the_program_has_them() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Full body !!! 135 !!! A | Squat -> 3x5 @ 1r | Press -> 3x5 @ 0.5r !!!  !!! warmup = bar x10, 40% x5, 60% x3, 80% x2",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("next", "", dir).success().stdout(contains(
+            "Squat -> 3x5 @ 135 lb\n    Warm-up: 10 @ 45 lb, 5 @ 50 lb, 3 @ 80 lb, 2 @ 105 lb\n    Plates: 45 lb bar + 45 per side\nPress -> 3x5 @ 70 lb\n    Warm-up: 10 @ 45 lb, 2 @ 55 lb\n",
+        ));
+    });
+}
+
+#[test]
+fn shows_plates_to_load() {
+    in_clean_dir(|dir| {
+        assert("plates 275", "", dir)
+            .success()
+            .stdout(contains("Plates: 45 lb bar + 45, 45, 25 per side"));
+        assert("plates inf", "", dir).failure();
+        assert("plates 1e9", "", dir).failure();
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Full body !!! 135 !!! A | Squat -> 3x5 @ 1r | Curl -> 3x10 @ add5 equipment dumbbell !!! Curl = 20 !!! bar = 35 !! plates = 45x2 10x1",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("next", "", dir).success().stdout(contains(
+            "Squat -> 3x5 @ 135 lb\n    Plates: can't load 135 lb exactly, nearest is 125 lb: 35 lb bar + 45 per side\nCurl -> 3x10 @ 20 lb\n",
+        ));
+        assert("plates 275", "", dir).success().stdout(contains(
+            "Plates: can't load 275 lb exactly, nearest is 235 lb: 35 lb bar + 45, 45, 10 per side",
+        ));
+    });
+}
+
+#[test]
+fn lists_program_templates() {
+    in_clean_dir(|dir| {
+        assert("programs list", "", dir)
+            .success()
+            .stdout(contains("gzcl-4day (v1)"))
+            .stdout(contains("gzcl-4day-stages (v1)"))
+            .stdout(contains("gzclp-3day (v1)"))
+            .stdout(contains("531-bbb (v1)"))
+            .stdout(contains("starting-strength (v1)"))
+            .stdout(contains("ppl-6day (v1)"));
+    });
+}
+
+#[test]
+fn shows_program_template() {
+    in_clean_dir(|dir| {
+        assert("programs show starting-strength", "", dir)
+            .success()
+            .stdout(contains("Starting 'squat' reference weight: 1.35r"))
+            .stdout(contains("=== Day: A ===\nSquat -> 3x5 @ 1r[squat]"))
+            .stdout(contains("Power clean -> 5x3 @ 1r[clean]"));
+        assert("programs show gzclp-3day", "", dir)
+            .success()
+            .stdout(contains("Lat pulldown -> 2x15,1x15-25 @ add10 progress gzclp-t3 equipment machine (starting at 50)"));
+        assert("programs show not-a-program", "", dir)
+            .failure()
+            .stderr(contains("No program template named 'not-a-program'"));
+    });
+}
+
+#[test]
+fn starts_program_from_template() {
+    in_clean_dir(|dir| {
+        assert("start --template starting-strength -r 100", "", dir)
+            .success()
+            .stdout(contains("Started program: Starting Strength"));
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("=== Day: A ==="))
+            .stdout(contains("Bench press -> 3x5 @ 100 lb"));
+    });
+}
+
+#[test]
+fn starts_program_with_named_reference_weights() {
+    in_clean_dir(|dir| {
+        assert(
+            "start --template starting-strength -r 100 --reference squat=185 -R press=70",
+            "",
+            dir,
+        )
+        .success();
+        assert("status", "", dir)
+            .success()
+            .stdout(contains("Current 'squat' reference weight: 185 lb\n"))
+            .stdout(contains("Starting 'squat' reference weight: 185 lb\n"))
+            .stdout(contains("Current 'deadlift' reference weight: 160 lb\n"))
+            .stdout(contains("Current 'press' reference weight: 70 lb\n"));
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Squat -> 3x5 @ 185 lb"))
+            .stdout(contains("Deadlift -> 1x5 @ 160 lb"));
+        assert("start --template starting-strength -R curl=50", "", dir)
+            .failure()
+            .stderr(contains("no reference weight named 'curl'"));
+    });
+}
+
 #[test]
 fn starting_program_needs_reference_weight() {
     in_clean_dir(|dir| {
@@ -97,7 +402,259 @@ fn completes_workout() {
             File::open(Path::new(&format!("{dir}/yawa_save_data/lift_history.txt"))).unwrap();
         file.read_to_string(&mut history_file_data).unwrap();
 
-        assert!(history_file_data.contains("Barbell Row -> 3x10 @ 65 | NotCompleted\n"));
+        assert!(history_file_data.contains("[barbell row] Barbell Row -> 3x10 @ 65 lb | NotCompleted\n"));
+    });
+}
+
+#[test]
+fn records_reps_weight_and_rpe() {
+    in_clean_dir(|dir| {
+        assert("start -r 100", "", dir);
+        assert("complete", "y\n3\n3\n3\n3\n8\n\n8\nn\nn\ny\n\n\n20\n25\n\nn\n", dir)
+            .success()
+            .stdout(contains("set 5 of 5 (3+): how many reps? [enter for 3]"))
+            .stdout(contains("what weight did you add (lb, below zero for assistance)? [enter for 20]"));
+
+        let mut history_file_data = String::new();
+        let mut file =
+            File::open(Path::new(&format!("{dir}/yawa_save_data/lift_history.txt"))).unwrap();
+        file.read_to_string(&mut history_file_data).unwrap();
+
+        assert!(history_file_data
+            .contains("Weighted Pullup -> 4x3,1x3+ @ bw+20 lb | Performed 3,3,3,3,8 @ 20 RPE 8\n"));
+        assert!(history_file_data.contains("Face Pull -> 2x15,1x15-25 @ 30 lb | Performed 15,15,20 @ 25\n"));
+    });
+}
+
+#[test]
+fn asks_again_for_an_rpe_off_the_scale() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(format!("{dir}/my_plan.txt"), "Squats !!! 100 !!! A | Squat -> 1x5 @ 1r").unwrap();
+        assert("start -p my_plan.txt", "", dir);
+        assert("complete", "y\n5\n\n37\n0\n8\n", dir)
+            .success()
+            .stdout(contains("an RPE is from 1 to 10").count(2));
+
+        let history_file_data =
+            read_to_string(format!("{dir}/yawa_save_data/lift_history.txt")).unwrap();
+        assert!(history_file_data.contains("Squat -> 1x5 @ 100 lb | Performed 5 @ 100 RPE 8\n"));
+    });
+}
+
+const SUPERSET_PROGRAM: &str = "Pull !!! 100 !!! Pull | [Face Pull -> 2x15,1x15-25 @ add20 equipment machine + Cable Curl -> 2x15,1x15-25 @ add20 equipment machine] !!! Face Pull = 30 !! Cable Curl = 20";
+
+#[test]
+fn shows_supersets_as_blocks() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(format!("{dir}/my_plan.txt"), SUPERSET_PROGRAM).unwrap();
+        assert("start -p my_plan.txt", "", dir);
+        assert("next", "", dir).success().stdout(contains(
+            "A1. Face Pull -> 2x15,1x15-25 @ 30 lb\nA2. Cable Curl -> 2x15,1x15-25 @ 20 lb\n",
+        ));
+    });
+}
+
+#[test]
+fn completes_supersets_set_by_set() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(format!("{dir}/my_plan.txt"), SUPERSET_PROGRAM).unwrap();
+        assert("start -p my_plan.txt", "", dir);
+        assert("complete", "y\ny\n\n\n\n\n20\n\n\n\n\n\n", dir)
+            .success()
+            .stdout(contains("Superset: Face Pull + Cable Curl"))
+            .stdout(contains("Face Pull: set 3 of 3 (15-25): how many reps? [enter for 25]"))
+            .stdout(contains("Cable Curl: set 3 of 3 (15-25): how many reps? [enter for 25]"));
+
+        let mut history_file_data = String::new();
+        let mut file =
+            File::open(Path::new(&format!("{dir}/yawa_save_data/lift_history.txt"))).unwrap();
+        file.read_to_string(&mut history_file_data).unwrap();
+
+        assert!(history_file_data.contains("Face Pull -> 2x15,1x15-25 @ 30 lb | Performed 15,15,20 @ 30\n"));
+        assert!(history_file_data.contains("Cable Curl -> 2x15,1x15-25 @ 20 lb | Performed 15,15,25 @ 20\n"));
+    });
+}
+
+#[test]
+fn shows_rest_and_tempo() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Legs !!! 200 !!! A | Squat -> 2x3 @ 1r rest 3min tempo 3010",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("next", "", dir).success().stdout(contains(
+            "Squat -> 2x3 @ 200 lb\n    Plates: 45 lb bar + 45, 25, 5, 2.5 per side\n    Rest: 3min between sets\n    Tempo: 3010, 12s under tension per set\n",
+        ));
+        assert("complete", "y\n\n\n\n\n", dir)
+            .success()
+            .stdout(contains("set 1 of 2 (3): how many reps? [enter for 3]         ... rest 3min\n"));
+    });
+}
+
+#[test]
+fn records_conditioning() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Legs !!! 200 !!! A | Sled push -> 2x40m @ add20 equipment machine | Row -> 1x2km | Assault bike -> 1x20cal | Burpees -> 3xEMOM(5) | Cindy -> 1xAMRAP(20min) !!! Sled push = 90",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("next", "", dir).success().stdout(contains(
+            "Sled push -> 2x40m @ 90 lb\nRow -> 1x2km\nAssault bike -> 1x20cal\nBurpees -> 3xEMOM(5)\nCindy -> 1xAMRAP(20min)\n",
+        ));
+        assert(
+            "complete",
+            "y\n35\n38\n\n\ny\n480\n\ny\n70\n\ny\n\n\n4\n\ny\n14\n\n",
+            dir,
+        )
+        .success()
+        .stdout(contains("set 1 of 2 (40m): how many seconds did it take? [enter to skip]"))
+        .stdout(contains("set 1 of 3 (EMOM(5)): how many reps? [enter for 5]"))
+        .stdout(contains("set 1 of 1 (AMRAP(20min)): how many rounds? [enter to skip]"));
+
+        let mut history_file_data = String::new();
+        let mut file =
+            File::open(Path::new(&format!("{dir}/yawa_save_data/lift_history.txt"))).unwrap();
+        file.read_to_string(&mut history_file_data).unwrap();
+
+        assert!(history_file_data.contains("Sled push -> 2x40m @ 90 lb | Performed 1,1 in 35s,38s @ 90\n"));
+        assert!(history_file_data.contains("Row -> 1x2km | Performed 1 in 8min\n"));
+        assert!(history_file_data.contains("Burpees -> 3xEMOM(5) | Performed 5,5,4\n"));
+        assert!(history_file_data.contains("Cindy -> 1xAMRAP(20min) | Performed 14\n"));
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Sled push -> 2x40m @ 110 lb\n"));
+    });
+}
+
+#[test]
+fn records_sets_done_in_parts() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Arms !!! 50 !!! A | Curl -> 1x10 drop 2 @ 1r equipment dumbbell | Pushdown -> 1x8+rp3 @ 0.8r equipment machine | Clean -> 1x(2+2+2) @ 1r",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("next", "", dir).success().stdout(contains(
+            "Curl -> 1x10 drop 2 @ 50 lb\n    Drops: 40 lb, 30 lb\nPushdown -> 1x8+rp3 @ 40 lb\nClean -> 1x(2+2+2) @ 50 lb\n",
+        ));
+        assert(
+            "complete",
+            "y\n\n7\n4\n\n\ny\n\n3\n2\n2\n\n\ny\n\n\n\n\n\n",
+            dir,
+        )
+        .success()
+        .stdout(contains(
+            "set 1 of 1 (10 drop 2), part 2 of 3 at 40 lb: how many reps? [enter to skip]",
+        ))
+        .stdout(contains("set 1 of 1 (8+rp3), part 1 of 4: how many reps? [enter for 8]"))
+        .stdout(contains("set 1 of 1 ((2+2+2)), part 3 of 3: how many reps? [enter for 2]"));
+
+        let mut history_file_data = String::new();
+        let mut file =
+            File::open(Path::new(&format!("{dir}/yawa_save_data/lift_history.txt"))).unwrap();
+        file.read_to_string(&mut history_file_data).unwrap();
+        assert!(history_file_data
+            .contains("Curl -> 1x10 drop 2 @ 50 lb | Performed 10+7+4 @ 50\n"));
+        assert!(history_file_data
+            .contains("Pushdown -> 1x8+rp3 @ 40 lb | Performed 8+3+2+2 @ 40\n"));
+        assert!(history_file_data.contains("Clean -> 1x(2+2+2) @ 50 lb | Performed 2+2+2 @ 50\n"));
+    });
+}
+
+#[test]
+fn asks_about_each_side() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Legs !!! 200 !!! A | Split squat -> 2x10/side @ add5/hand equipment dumbbell !!! Split squat = 30",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Split squat -> 2x10 per side @ 30 lb per hand\n"));
+        assert("complete", "y\n\n9\n\n\n\n\n", dir)
+            .success()
+            .stdout(contains("left side, set 1 of 2 (10): how many reps? [enter for 10]"))
+            .stdout(contains("right side, set 2 of 2 (10): how many reps? [enter for 10]"))
+            .stdout(contains("what weight did you use (lb per hand)? [enter for 30]"))
+            .stdout(contains("Volume: 2340 lb"));
+
+        let mut history_file_data = String::new();
+        let mut file =
+            File::open(Path::new(&format!("{dir}/yawa_save_data/lift_history.txt"))).unwrap();
+        file.read_to_string(&mut history_file_data).unwrap();
+        assert!(history_file_data
+            .contains("Split squat -> 2x10 per side @ 30 lb per hand | Performed 10/9,10/10 @ 30\n"));
+    });
+}
+
+#[test]
+fn records_back_off_sets() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Legs !!! 200 !!! A | Squat -> 1x3+ @ 1r, 2x8 @ 0.8r",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Squat -> 1x3+ @ 200 lb, 2x8 @ 160 lb\n"));
+        assert("complete", "y\n5\n8\n6\n\n155\n\n", dir)
+            .success()
+            .stdout(contains("set 3 of 3 (8): how many reps? [enter for 8]"))
+            .stdout(contains("what weight did you use for sets 2 to 3 (lb)? [enter for 160]"));
+
+        let mut history_file_data = String::new();
+        let mut file =
+            File::open(Path::new(&format!("{dir}/yawa_save_data/lift_history.txt"))).unwrap();
+        file.read_to_string(&mut history_file_data).unwrap();
+        assert!(history_file_data
+            .contains("Squat -> 1x3+ @ 200 lb, 2x8 @ 160 lb | Performed 5,8,6 @ 200, 155\n"));
+    });
+}
+
+#[test]
+fn announces_deloads() {
+    in_clean_dir(|dir| {
+        create_dir_all(dir).unwrap();
+        write(
+            format!("{dir}/my_plan.txt"),
+            "Squats !!! 100 !!! A | Squat -> 3x5 @ 1r !!!  !!! deload = 10% after 2",
+        )
+        .unwrap();
+        assert("start -p my_plan.txt", "", dir).success();
+        assert("complete", "n\n", dir)
+            .success()
+            .stdout(contains("Deload").not());
+        assert("complete", "n\n", dir).success().stdout(contains(
+            "Deload: reference weight stalled 2 times in a row, dropping it from 100 lb to 90 lb",
+        ));
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Squat -> 3x5 @ 90 lb"));
+
+        let mut history_file_data = String::new();
+        let mut file =
+            File::open(Path::new(&format!("{dir}/yawa_save_data/lift_history.txt"))).unwrap();
+        file.read_to_string(&mut history_file_data).unwrap();
+        assert!(history_file_data.contains(
+            ": Deload: reference weight stalled 2 times in a row, dropping it from 100 lb to 90 lb\n"
+        ));
     });
 }
 
@@ -110,7 +667,32 @@ fn prints_next_workout() {
         assert("start -r 100", "", dir);
         assert("next", "", dir)
             .success()
-            .stdout(contains("Weighted Pullup -> 4x3,1x3+ @ 20"));
+            .stdout(contains("Weighted Pullup -> 4x3,1x3+ @ bw+20 lb"));
+    })
+}
+
+#[test]
+fn assists_and_loads_bodyweight_lifts() {
+    in_clean_dir(|dir| {
+        assert("start -r 45", "", dir);
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Weighted Pullup -> 4x3,1x3+ @ bw-5 lb\nPullup -> 3x7+\n"));
+        assert("bodyweight 180", "", dir)
+            .success()
+            .stdout(contains("Logged bodyweight: 180 lb"));
+        assert("bodyweight -- -50", "", dir)
+            .failure()
+            .stderr(contains("Bodyweight must be a number above zero: -50"));
+        assert("bodyweight NaN", "", dir)
+            .failure()
+            .stderr(contains("Bodyweight must be a number above zero: NaN"));
+        assert("status", "", dir)
+            .success()
+            .stdout(contains("Bodyweight: 180 lb"));
+        assert("next", "", dir).success().stdout(contains(
+            "Weighted Pullup -> 4x3,1x3+ @ bw-5 lb\n    Load: 175 lb with your bodyweight\nPullup -> 3x7+\n    Load: 180 lb with your bodyweight\n",
+        ));
     })
 }
 
@@ -122,8 +704,63 @@ fn works_with_current_major_version_data() {
             .stdout(contains("Workouts completed: 9"));
         assert("next", "", dir)
             .success()
-            .stdout(contains("Bench press -> 4x3,1x3+ @ 205"))
-            .stdout(contains("Tricep Cable Pressdown -> 2x15,1x15-25 @ 40"));
+            .stdout(contains("Bench press -> 4x3,1x3+ @ 205 lb"))
+            .stdout(contains("Tricep Cable Pressdown -> 2x15,1x15-25 @ 40 lb"));
+    })
+}
+
+#[test]
+fn says_when_the_template_has_changed() {
+    in_clean_dir(|dir| {
+        assert("start --template starting-strength -r 105", "", dir).success();
+        assert("status", "", dir)
+            .success()
+            .stdout(contains("template").not());
+        let saved = read_to_string(format!("{dir}/yawa_save_data/program.json")).unwrap();
+        assert!(saved.contains("\"starting-strength\",\n    1\n"));
+        write(
+            format!("{dir}/yawa_save_data/program.json"),
+            saved.replace("\"starting-strength\",\n    1\n", "\"starting-strength\",\n    0\n"),
+        )
+        .unwrap();
+        assert("status", "", dir).success().stdout(contains(
+            "The 'starting-strength' template is at v1 now, and this program was started from v0. Start it again to use the new version.",
+        ));
+    })
+}
+
+#[test]
+fn keeps_weights_of_lifts_whose_sets_change() {
+    in_clean_dir(|dir| {
+        let saved = read_to_string(
+            "tests/test_assets/major_version_saved_data/yawa_save_data/program.json",
+        )
+        .unwrap()
+        .replace(
+            "Pushup -> 3x15+ | Tricep Cable Pressdown -> 2x15,1x15-25 @ add20",
+            "Pushup -> 3x15+ | Tricep Cable Pressdown -> 3x12 @ add20",
+        );
+        create_dir_all(format!("{dir}/yawa_save_data")).unwrap();
+        write(format!("{dir}/yawa_save_data/program.json"), saved).unwrap();
+        assert("next", "", dir)
+            .success()
+            .stdout(contains("Tricep Cable Pressdown -> 3x12 @ 40 lb"));
+    })
+}
+
+#[test]
+fn gives_lifts_of_old_saves_the_default_programs_equipment() {
+    in_clean_dir(|dir| {
+        let saved = read_to_string(
+            "tests/test_assets/major_version_saved_data/yawa_save_data/program.json",
+        )
+        .unwrap()
+        .replace("\"current_day\": 1", "\"current_day\": 0");
+        create_dir_all(format!("{dir}/yawa_save_data")).unwrap();
+        write(format!("{dir}/yawa_save_data/program.json"), saved).unwrap();
+        assert("next", "", dir).success().stdout(contains(
+            "Weighted Pullup -> 4x3,1x3+ @ bw+75 lb\nPullup -> 3x7+\nBarbell Row -> 3x10 @ 135 lb\n    Plates: 45 lb bar + 45 per side\nFace Pull -> 2x15,1x15-25 @ 70 lb\nCable Curl -> 2x15,1x15-25 @ 80 lb\n",
+        ));
     })
 }
 